@@ -0,0 +1,177 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, Data, DeriveInput, Expr, Fields, Ident, LitChar, Token,
+};
+
+/// Derives `ParseableCharacters`, `TryFrom<char>` and `Display` for a grid tile enum.
+///
+/// Every variant needs a `#[tile('c')]` attribute naming the character it parses
+/// from and renders as. Variants that carry data are built with `Default::default()`
+/// unless a payload is given, as in `#[tile('^', default = Status::Deactivated)]`.
+/// `valid_chars` lists the characters in variant order.
+#[proc_macro_derive(Tile, attributes(tile))]
+pub fn derive_tile(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct TileAttr {
+    character: LitChar,
+    default: Option<Expr>,
+}
+
+impl Parse for TileAttr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let character = input.parse()?;
+        let mut default = None;
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let key: Ident = input.parse()?;
+            if key != "default" {
+                return Err(syn::Error::new(
+                    key.span(),
+                    "expected `default = <payload>`",
+                ));
+            }
+            input.parse::<Token![=]>()?;
+            default = Some(input.parse()?);
+            input.parse::<Option<Token![,]>>()?;
+        }
+        Ok(Self { character, default })
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            name,
+            "Tile can only be derived for enums",
+        ));
+    };
+
+    let mut characters: Vec<LitChar> = vec![];
+    let mut constructors = vec![];
+    let mut patterns = vec![];
+    for variant in &data.variants {
+        let ident = &variant.ident;
+        let attr = variant
+            .attrs
+            .iter()
+            .find(|a| a.path().is_ident("tile"))
+            .ok_or_else(|| syn::Error::new_spanned(variant, "missing #[tile('c')] attribute"))?;
+        let TileAttr { character, default } = attr.parse_args()?;
+        if characters.iter().any(|c| c.value() == character.value()) {
+            return Err(syn::Error::new_spanned(
+                &character,
+                "tile character is used by more than one variant",
+            ));
+        }
+
+        let constructor = match (&variant.fields, default) {
+            (Fields::Unit, None) => quote!(Self::#ident),
+            (Fields::Unnamed(fields), Some(payload)) if fields.unnamed.len() == 1 => {
+                quote!(Self::#ident(#payload))
+            }
+            (Fields::Unnamed(fields), None) => {
+                let payloads = fields
+                    .unnamed
+                    .iter()
+                    .map(|_| quote!(::core::default::Default::default()));
+                quote!(Self::#ident(#(#payloads),*))
+            }
+            (Fields::Named(fields), None) => {
+                let payloads = fields.named.iter().map(|f| {
+                    let field = &f.ident;
+                    quote!(#field: ::core::default::Default::default())
+                });
+                quote!(Self::#ident { #(#payloads),* })
+            }
+            (_, Some(_)) => {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "`default` is only supported on variants with a single unnamed field",
+                ));
+            }
+        };
+        characters.push(character);
+        constructors.push(constructor);
+        patterns.push(quote!(Self::#ident { .. }));
+    }
+    let rendered = characters.iter().map(|c| c.value().to_string());
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::aoc_utils::ParseableCharacters for #name #ty_generics #where_clause {
+            fn valid_chars() -> ::std::vec::Vec<char> {
+                ::std::vec![#(#characters),*]
+            }
+        }
+
+        impl #impl_generics ::core::convert::TryFrom<char> for #name #ty_generics #where_clause {
+            type Error = ::aoc_utils::BadTileTypeError;
+
+            fn try_from(value: char) -> ::core::result::Result<Self, Self::Error> {
+                match value {
+                    #(#characters => ::core::result::Result::Ok(#constructors),)*
+                    _ => ::core::result::Result::Err(::aoc_utils::BadTileTypeError(value)),
+                }
+            }
+        }
+
+        impl #impl_generics ::core::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(match self {
+                    #(#patterns => #rendered,)*
+                })
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+    use test_case::test_case;
+
+    #[test_case(parse_quote! {
+        enum Slot { #[tile('.')] Empty, Wall }
+    }, "missing #[tile('c')] attribute"; "missing attribute")]
+    #[test_case(parse_quote! {
+        enum Slot { #[tile('.')] Empty, #[tile('.')] Wall }
+    }, "tile character is used by more than one variant"; "duplicate character")]
+    #[test_case(parse_quote! {
+        enum Slot { #[tile('^', fallback = Status::On)] Splitter(Status) }
+    }, "expected `default = <payload>`"; "wrong key")]
+    #[test_case(parse_quote! {
+        enum Slot { #[tile('^', default = )] Splitter(Status) }
+    }, "unexpected end of input, expected an expression"; "missing payload")]
+    #[test_case(parse_quote! {
+        enum Slot { #[tile('.', default = 1)] Empty }
+    }, "`default` is only supported on variants with a single unnamed field"; "payload on unit variant")]
+    #[test_case(parse_quote! {
+        enum Slot { #[tile("#")] Wall }
+    }, "expected character literal"; "string instead of char")]
+    #[test_case(parse_quote! {
+        struct Slot;
+    }, "Tile can only be derived for enums"; "struct")]
+    fn test_expand_errors(input: DeriveInput, exp: &str) {
+        let actual = expand(&input).unwrap_err();
+        assert_eq!(actual.to_string(), exp);
+    }
+
+    #[test]
+    fn test_expand_is_hygienic() {
+        let input: DeriveInput = parse_quote! {
+            enum Slot { #[tile('.')] Empty, #[tile('^', default = Status::On)] Splitter(Status) }
+        };
+        let actual = expand(&input).unwrap().to_string();
+        assert!(actual.contains(":: std :: vec !"));
+        assert!(!actual.replace(":: std :: vec !", "").contains("vec !"));
+    }
+}
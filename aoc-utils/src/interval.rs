@@ -0,0 +1,298 @@
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{char, digit1, line_ending, space0},
+    combinator::map_res,
+    multi::separated_list1,
+    sequence::{delimited, separated_pair},
+    IResult,
+};
+use num::PrimInt;
+use std::str::FromStr;
+
+/// An inclusive range of integers, `min..=max`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub struct Interval<T> {
+    min: T,
+    max: T,
+}
+
+impl<T: PrimInt> Interval<T> {
+    /// Panics if `min` is greater than `max`.
+    pub fn new(min: T, max: T) -> Self {
+        assert!(min <= max, "interval minimum is greater than its maximum");
+        Self { min, max }
+    }
+    pub fn min(&self) -> T {
+        self.min
+    }
+    pub fn max(&self) -> T {
+        self.max
+    }
+    /// How many values the interval covers, or `None` if the count is too big
+    /// for `T`, as it is when the interval spans the whole type.
+    pub fn len(&self) -> Option<T> {
+        self.max.checked_sub(&self.min)?.checked_add(&T::one())
+    }
+    pub fn contains(&self, value: &T) -> bool {
+        self.min <= *value && *value <= self.max
+    }
+}
+
+/// A set of integers stored as sorted, disjoint intervals. Overlapping and
+/// adjacent intervals are merged as they are inserted.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { intervals: vec![] }
+    }
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// The number of merged intervals, not the number of values covered.
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+    pub fn insert(&mut self, interval: Interval<T>) {
+        let one = T::one();
+        let start = self
+            .intervals
+            .partition_point(|i| i.max.saturating_add(one) < interval.min);
+        let end = self
+            .intervals
+            .partition_point(|i| i.min <= interval.max.saturating_add(one));
+        let mut merged = interval;
+        if start < end {
+            merged.min = merged.min.min(self.intervals[start].min);
+            merged.max = merged.max.max(self.intervals[end - 1].max);
+        }
+        self.intervals.splice(start..end, [merged]);
+    }
+    pub fn contains(&self, value: &T) -> bool {
+        let idx = self.intervals.partition_point(|i| i.max < *value);
+        self.intervals.get(idx).is_some_and(|i| i.min <= *value)
+    }
+    /// How many values the set covers, or `None` if the count is too big for `T`.
+    pub fn total_len(&self) -> Option<T> {
+        self.intervals
+            .iter()
+            .try_fold(T::zero(), |acc, interval| acc.checked_add(&interval.len()?))
+    }
+    pub fn union(&self, other: &Self) -> Self {
+        let mut out = self.clone();
+        for interval in &other.intervals {
+            out.insert(*interval);
+        }
+        out
+    }
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (mut i, mut j) = (0, 0);
+        let mut intervals = vec![];
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            let (min, max) = (a.min.max(b.min), a.max.min(b.max));
+            if min <= max {
+                intervals.push(Interval::new(min, max));
+            }
+            if a.max < b.max {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { intervals }
+    }
+    /// Every value in `self` that is not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let one = T::one();
+        let mut intervals = vec![];
+        let mut j = 0;
+        for interval in &self.intervals {
+            while j < other.intervals.len() && other.intervals[j].max < interval.min {
+                j += 1;
+            }
+            let mut remaining = Some(interval.min);
+            for cut in other.intervals[j..]
+                .iter()
+                .take_while(|c| c.min <= interval.max)
+            {
+                let Some(min) = remaining else { break };
+                if cut.min > min {
+                    intervals.push(Interval::new(min, cut.min - one));
+                }
+                remaining = (cut.max < interval.max).then(|| cut.max + one);
+            }
+            if let Some(min) = remaining {
+                intervals.push(Interval::new(min, interval.max));
+            }
+        }
+        Self { intervals }
+    }
+}
+
+impl<T: PrimInt> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+/// Parses `a-b`.
+pub fn parse_interval<T>(inp: &str) -> IResult<&str, Interval<T>>
+where
+    T: PrimInt + FromStr,
+{
+    let (rest, (min, max)) = separated_pair(
+        map_res(digit1, str::parse::<T>),
+        char('-'),
+        map_res(digit1, str::parse::<T>),
+    )(inp)?;
+    if min > max {
+        return Err(nom::Err::Error(nom::error::Error::new(
+            inp,
+            nom::error::ErrorKind::Verify,
+        )));
+    }
+    Ok((rest, Interval::new(min, max)))
+}
+
+/// Parses a list of `a-b` intervals separated by commas or newlines.
+pub fn parse_intervals<T>(inp: &str) -> IResult<&str, Vec<Interval<T>>>
+where
+    T: PrimInt + FromStr,
+{
+    separated_list1(
+        delimited(space0, alt((tag(","), line_ending)), space0),
+        parse_interval,
+    )(inp)
+}
+
+/// Parses a list of `a-b` intervals into a merged set.
+pub fn parse_interval_set<T>(inp: &str) -> IResult<&str, IntervalSet<T>>
+where
+    T: PrimInt + FromStr,
+{
+    let (rest, intervals) = parse_intervals(inp)?;
+    Ok((rest, intervals.into_iter().collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn set(intervals: &[(u64, u64)]) -> IntervalSet<u64> {
+        set_of(intervals)
+    }
+
+    fn set_of<T: PrimInt>(intervals: &[(T, T)]) -> IntervalSet<T> {
+        intervals
+            .iter()
+            .map(|(min, max)| Interval::new(*min, *max))
+            .collect()
+    }
+
+    #[test_case(&[(3, 5), (10, 14), (16, 20), (12, 18)], &[(3, 5), (10, 20)]; "overlapping")]
+    #[test_case(&[(1, 2), (3, 4)], &[(1, 4)]; "adjacent")]
+    #[test_case(&[(1, 10), (3, 4)], &[(1, 10)]; "nested")]
+    #[test_case(&[(5, 6), (5, 6)], &[(5, 6)]; "duplicate")]
+    #[test_case(&[(8, 9), (1, 2), (5, 5)], &[(1, 2), (5, 5), (8, 9)]; "out of order")]
+    #[test_case(&[(1, 2), (6, 7), (4, 4), (3, 5)], &[(1, 7)]; "bridging")]
+    fn test_insert_merges(inp: &[(u64, u64)], exp: &[(u64, u64)]) {
+        let actual = set(inp);
+        assert_eq!(
+            actual
+                .iter()
+                .map(|i| (i.min(), i.max()))
+                .collect::<Vec<_>>(),
+            exp
+        );
+    }
+
+    #[test]
+    fn test_insert_at_type_limits() {
+        let actual: IntervalSet<u8> = [Interval::new(250, 255), Interval::new(0, 249)]
+            .into_iter()
+            .collect();
+        assert_eq!(actual.len(), 1);
+        assert!(actual.contains(&255));
+        assert_eq!(actual.total_len(), None);
+        assert_eq!(Interval::new(1_u8, 255).len(), Some(255));
+        assert_eq!(set_of::<u8>(&[(0, 100), (101, 254)]).total_len(), Some(255));
+        assert_eq!(set_of::<i8>(&[(-128, -1), (1, 127)]).total_len(), None);
+        assert_eq!(
+            set_of::<i8>(&[(-128, -100), (100, 127)]).total_len(),
+            Some(57)
+        );
+        assert_eq!(Interval::new(-128_i8, 127).len(), None);
+    }
+
+    #[test_case(1, false)]
+    #[test_case(3, true)]
+    #[test_case(5, true)]
+    #[test_case(8, false)]
+    #[test_case(17, true)]
+    #[test_case(21, false)]
+    fn test_contains(value: u64, exp: bool) {
+        let actual = set(&[(3, 5), (10, 14), (16, 20), (12, 18)]);
+        assert_eq!(actual.contains(&value), exp);
+    }
+
+    #[test]
+    fn test_total_len() {
+        assert_eq!(
+            set(&[(3, 5), (10, 14), (16, 20), (12, 18)]).total_len(),
+            Some(14)
+        );
+        assert_eq!(IntervalSet::<u64>::new().total_len(), Some(0));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(1, 10), (20, 30)]);
+        let b = set(&[(5, 22), (28, 28), (40, 41)]);
+        assert_eq!(a.union(&b), set(&[(1, 30), (40, 41)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 22), (28, 28)]));
+        assert_eq!(a.difference(&b), set(&[(1, 4), (23, 27), (29, 30)]));
+        assert_eq!(b.difference(&a), set(&[(11, 19), (40, 41)]));
+        assert_eq!(a.difference(&a), IntervalSet::new());
+    }
+
+    #[test_case("11-22,95-115,998-1012\n", &[(11, 22), (95, 115), (998, 1012)]; "commas")]
+    #[test_case("3-5\n10-14\r\n16-20\n\n1\n", &[(3, 5), (10, 14), (16, 20)]; "newlines")]
+    #[test_case("1-2, 3-4", &[(1, 2), (3, 4)]; "spaces")]
+    fn test_parse_intervals(inp: &str, exp: &[(u64, u64)]) {
+        let (_, actual) = parse_intervals::<u64>(inp).unwrap();
+        assert_eq!(
+            actual
+                .iter()
+                .map(|i| (i.min(), i.max()))
+                .collect::<Vec<_>>(),
+            exp
+        );
+    }
+
+    #[test]
+    fn test_parse_interval_set() {
+        let (rest, actual) = parse_interval_set::<u64>("3-5\n10-14\n16-20\n12-18\n\n1\n").unwrap();
+        assert_eq!(rest, "\n\n1\n");
+        assert_eq!(actual, set(&[(3, 5), (10, 20)]));
+        assert!(parse_interval::<u64>("5-3").is_err());
+    }
+}
@@ -1,16 +1,33 @@
+pub mod interval;
+pub mod parsing;
+pub mod polygon;
+pub mod regions;
+pub mod render;
+pub mod search;
+pub mod sparse;
+pub mod symmetry;
+pub mod transform;
+pub mod vector;
+
+extern crate self as aoc_utils;
+
+pub use aoc_derive::Tile;
+
 use itertools::Itertools;
 use nom::{
     character::complete::{newline, one_of},
-    combinator::map,
+    combinator::map_res,
+    error::{Error, ErrorKind},
     multi::{many0, many1},
     sequence::terminated,
     IResult,
 };
 use num::Integer;
 use std::fmt::{Debug, Display};
+use vector::Offset;
 
-#[derive(Debug)]
-pub struct BadTileTypeError;
+#[derive(Debug, PartialEq)]
+pub struct BadTileTypeError(pub char);
 
 pub fn parse_tile_type<T>(inp: &str) -> IResult<&str, T>
 where
@@ -18,9 +35,7 @@ where
     <T as TryFrom<char>>::Error: Debug,
 {
     let valid_tile_chars = T::valid_chars().iter().join("");
-    let res = map(one_of(valid_tile_chars.as_str()), |c| {
-        T::try_from(c).unwrap()
-    })(inp);
+    let res = map_res(one_of(valid_tile_chars.as_str()), T::try_from)(inp);
     res
 }
 
@@ -29,17 +44,11 @@ where
     T: ParseableCharacters + TryFrom<char> + Copy + PartialEq,
     <T as TryFrom<char>>::Error: Debug,
 {
-    let (inp, rows) = many1(terminated(many1(parse_tile_type), newline))(inp)?;
-    let mut collection = Collection(vec![]);
-    for (row_num, row) in rows.iter().enumerate() {
-        for (col_num, tile) in row.iter().enumerate() {
-            collection.push(Tile::new(
-                *tile,
-                Loc::new(col_num as isize, row_num as isize),
-            ));
-        }
+    let (rest, rows) = many1(terminated(many1(parse_tile_type), newline))(inp)?;
+    match Collection::from_rows(rows) {
+        Some(collection) => Ok((rest, collection)),
+        None => Err(nom::Err::Failure(Error::new(inp, ErrorKind::Verify))),
     }
-    Ok((inp, collection))
 }
 
 pub fn parse_collection_group<T>(inp: &str) -> IResult<&str, CollectionGroup<T>>
@@ -55,7 +64,7 @@ pub trait ParseableCharacters {
     fn valid_chars() -> Vec<char>;
 }
 
-#[derive(Debug, PartialEq, Copy, Clone, PartialOrd, Eq, Ord)]
+#[derive(Debug, PartialEq, Copy, Clone, PartialOrd, Eq, Ord, Hash)]
 pub struct Loc {
     x: isize,
     y: isize,
@@ -135,26 +144,77 @@ where
         f.write_str(&out)
     }
 }
+/// A rectangular grid of tiles stored densely in row-major order, so any `Loc`
+/// maps straight to an index in the backing buffer.
 #[derive(Default, Debug, Clone, PartialEq)]
-pub struct Collection<T>(Vec<Tile<T>>);
+pub struct Collection<T> {
+    tiles: Vec<Tile<T>>,
+    width: usize,
+    height: usize,
+}
 impl<T> Collection<T>
 where
     T: ParseableCharacters + Copy + TryFrom<char> + PartialEq,
 {
-    fn push(&mut self, tile: Tile<T>) {
-        self.0.push(tile)
+    /// Builds a collection from parsed rows, returning `None` if the rows are ragged.
+    fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        let tiles = rows
+            .into_iter()
+            .enumerate()
+            .flat_map(|(row_num, row)| {
+                row.into_iter().enumerate().map(move |(col_num, tile)| {
+                    Tile::new(tile, Loc::new(col_num as isize, row_num as isize))
+                })
+            })
+            .collect();
+        Some(Self {
+            tiles,
+            width,
+            height,
+        })
+    }
+    fn index_of(&self, loc: Loc) -> Option<usize> {
+        let x = usize::try_from(loc.x).ok()?;
+        let y = usize::try_from(loc.y).ok()?;
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+    pub fn contains(&self, loc: Loc) -> bool {
+        self.index_of(loc).is_some()
     }
     pub fn get_row(&self, row_num: isize) -> Row<'_, T> {
-        Row(self.0.iter().filter(|t| t.loc.y == row_num).collect())
+        Row(self.row_slice(row_num).iter().collect())
     }
     pub fn get_column(&self, col_num: isize) -> Column<'_, T> {
-        Row(self.0.iter().filter(|t| t.loc.x == col_num).collect())
+        Row(self.column_iter(col_num).collect())
+    }
+    /// The tiles of a single row, or an empty slice if the row is out of bounds.
+    pub fn row_slice(&self, row_num: isize) -> &[Tile<T>] {
+        match usize::try_from(row_num) {
+            Ok(row) if row < self.height => &self.tiles[row * self.width..(row + 1) * self.width],
+            _ => &[],
+        }
+    }
+    /// The tiles of a single column from top to bottom, empty if the column is out of bounds.
+    pub fn column_iter(&self, col_num: isize) -> impl Iterator<Item = &Tile<T>> {
+        usize::try_from(col_num)
+            .ok()
+            .filter(|col| *col < self.width)
+            .into_iter()
+            .flat_map(move |col| self.tiles.iter().skip(col).step_by(self.width))
+    }
+    pub fn rows(&self) -> impl Iterator<Item = &[Tile<T>]> {
+        self.tiles.chunks(self.width.max(1))
     }
     pub fn count_rows(&self) -> usize {
-        self.0.iter().unique_by(|t| t.loc.y).count()
+        self.height
     }
     pub fn count_columns(&self) -> usize {
-        self.0.iter().unique_by(|t| t.loc.x).count()
+        self.width
     }
     pub fn count_tile_type(&self, tile_type: &T) -> usize {
         self.tiles()
@@ -163,32 +223,121 @@ where
             .count()
     }
     pub fn len(&self) -> usize {
-        self.0.len()
+        self.tiles.len()
     }
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.tiles.is_empty()
     }
     pub fn tiles(&self) -> &Vec<Tile<T>> {
-        &self.0
+        &self.tiles
     }
     pub fn get_tile(&self, loc: Loc) -> Option<&Tile<T>> {
-        self.get_row(loc.y)
-            .0
-            .iter()
-            .find(|t| t.loc.x == loc.x)
-            .copied()
+        self.index_of(loc).map(|idx| &self.tiles[idx])
+    }
+    pub fn get_tile_mut(&mut self, loc: Loc) -> Option<&mut Tile<T>> {
+        self.index_of(loc).map(|idx| &mut self.tiles[idx])
     }
 
-    pub fn from_puzzle_input(puzzle_input: &str) -> Collection<T>
+    /// Each in-bounds neighbor along with the offset that reaches it. A
+    /// `Stencil` containing `(0, 0)` includes the tile at `loc` itself.
+    pub fn neighbors(&self, loc: Loc, neighborhood: &Neighborhood) -> Vec<(Offset, &Tile<T>)> {
+        neighborhood
+            .offsets()
+            .into_iter()
+            .filter_map(|offset| {
+                let neighbor = Loc::new(
+                    loc.x.checked_add(offset.get_dx())?,
+                    loc.y.checked_add(offset.get_dy())?,
+                );
+                Some((offset, self.get_tile(neighbor)?))
+            })
+            .collect()
+    }
+    /// Like `neighbors`, but paired with the compass direction each one lies
+    /// in. A `(0, 0)` stencil offset has no direction and is left out, and
+    /// stencil offsets pointing the same way share a direction.
+    pub fn neighbors_by_direction(
+        &self,
+        loc: Loc,
+        neighborhood: &Neighborhood,
+    ) -> Vec<(Direction, &Tile<T>)> {
+        self.neighbors(loc, neighborhood)
+            .into_iter()
+            .filter_map(|(offset, t)| {
+                Some((Direction::from_offset(offset.get_dx(), offset.get_dy())?, t))
+            })
+            .collect()
+    }
+    /// Counts every tile `neighbors` returns, so a `Stencil` containing `(0, 0)`
+    /// counts the tile at `loc` too.
+    pub fn count_neighbors_matching<F>(
+        &self,
+        loc: Loc,
+        neighborhood: &Neighborhood,
+        predicate: F,
+    ) -> usize
     where
-        <T as TryFrom<char>>::Error: Debug,
+        F: Fn(&Tile<T>) -> bool,
     {
-        parse_collection(puzzle_input).unwrap().1
+        self.neighbors(loc, neighborhood)
+            .into_iter()
+            .filter(|(_, t)| predicate(t))
+            .count()
+    }
+
+    /// Like `try_from_puzzle_input`, but panics with the error location.
+    pub fn from_puzzle_input(puzzle_input: &str) -> Collection<T> {
+        Self::try_from_puzzle_input(puzzle_input).unwrap_or_else(|e| panic!("{e}"))
     }
 }
 
 pub type CollectionGroup<T> = Vec<Collection<T>>;
 
+/// An axis-aligned rectangle of locations, inclusive of both corners.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub struct Rect {
+    min: Loc,
+    max: Loc,
+}
+impl Rect {
+    pub fn new(corner: Loc, opposite: Loc) -> Self {
+        Self {
+            min: Loc::new(corner.x.min(opposite.x), corner.y.min(opposite.y)),
+            max: Loc::new(corner.x.max(opposite.x), corner.y.max(opposite.y)),
+        }
+    }
+    /// The smallest rectangle covering every location, or `None` if there are none.
+    pub fn bounding(locs: impl IntoIterator<Item = Loc>) -> Option<Self> {
+        locs.into_iter().fold(None, |acc: Option<Rect>, loc| {
+            Some(match acc {
+                None => Rect::new(loc, loc),
+                Some(rect) => Rect::new(
+                    Loc::new(rect.min.x.min(loc.x), rect.min.y.min(loc.y)),
+                    Loc::new(rect.max.x.max(loc.x), rect.max.y.max(loc.y)),
+                ),
+            })
+        })
+    }
+    pub fn min(&self) -> Loc {
+        self.min
+    }
+    pub fn max(&self) -> Loc {
+        self.max
+    }
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x).unsigned_abs() + 1
+    }
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y).unsigned_abs() + 1
+    }
+    pub fn area(&self) -> usize {
+        self.width() * self.height()
+    }
+    pub fn contains(&self, loc: Loc) -> bool {
+        (self.min.x..=self.max.x).contains(&loc.x) && (self.min.y..=self.max.y).contains(&loc.y)
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Direction {
     North,
@@ -246,6 +395,45 @@ impl Direction {
             Direction::NorthWest,
         ]
     }
+    /// The compass direction an offset points toward, or `None` for a zero offset.
+    pub fn from_offset(dx: isize, dy: isize) -> Option<Self> {
+        Some(match (dx.signum(), dy.signum()) {
+            (0, -1) => Direction::North,
+            (1, 0) => Direction::East,
+            (0, 1) => Direction::South,
+            (-1, 0) => Direction::West,
+            (1, -1) => Direction::NorthEast,
+            (1, 1) => Direction::SouthEast,
+            (-1, 1) => Direction::SouthWest,
+            (-1, -1) => Direction::NorthWest,
+            _ => return None,
+        })
+    }
+}
+
+/// Which surrounding tiles count as neighbors of a location.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// The four orthogonally adjacent tiles.
+    Cardinal,
+    /// All eight adjacent tiles, diagonals included.
+    All,
+    /// Arbitrary `(dx, dy)` offsets, kept exactly as given, so `(0, 0)` is the
+    /// tile itself and offsets pointing the same way stay distinct. Only
+    /// `neighbors_by_direction` drops `(0, 0)`.
+    Stencil(Vec<(isize, isize)>),
+}
+impl Neighborhood {
+    fn offsets(&self) -> Vec<Offset> {
+        match self {
+            Neighborhood::Cardinal => Self::unit_offsets(Direction::get_cardinal()),
+            Neighborhood::All => Self::unit_offsets(Direction::get_all()),
+            Neighborhood::Stencil(offsets) => offsets.iter().copied().map(Offset::from).collect(),
+        }
+    }
+    fn unit_offsets(directions: Vec<Direction>) -> Vec<Offset> {
+        directions.iter().map(Direction::to_offset).collect()
+    }
 }
 
 #[derive(Debug, Copy, Clone, Ord, Eq, PartialOrd)]
@@ -296,35 +484,29 @@ mod tests {
     use super::*;
     use test_case::test_case;
 
-    #[derive(Debug, PartialEq, Copy, Clone)]
-    enum LavaTile {
+    #[derive(Debug, PartialEq, Copy, Clone, Tile)]
+    pub(crate) enum LavaTile {
+        #[tile('.')]
         Ash,
+        #[tile('#')]
         Rocks,
     }
-    impl Display for LavaTile {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            f.write_str(match self {
-                LavaTile::Ash => ".",
-                LavaTile::Rocks => "#",
-            })
-        }
-    }
-
-    impl TryFrom<char> for LavaTile {
-        type Error = BadTileTypeError;
 
-        fn try_from(value: char) -> Result<Self, Self::Error> {
-            match value {
-                '.' => Ok(Self::Ash),
-                '#' => Ok(Self::Rocks),
-                _ => unreachable!(),
-            }
-        }
+    #[derive(Debug, PartialEq, Copy, Clone, Default)]
+    enum Status {
+        Activated,
+        #[default]
+        Deactivated,
     }
-    impl ParseableCharacters for LavaTile {
-        fn valid_chars() -> Vec<char> {
-            vec!['#', '.']
-        }
+
+    #[derive(Debug, PartialEq, Copy, Clone, Tile)]
+    enum ManifoldSlot {
+        #[tile('.')]
+        Empty,
+        #[tile('^', default = Status::Activated)]
+        Splitter(Status),
+        #[tile('v')]
+        Reflector(Status),
     }
 
     #[test_case(".", Ok(("", LavaTile::Ash)); "Ash")]
@@ -334,11 +516,31 @@ mod tests {
         assert_eq!(actual, exp);
     }
 
+    #[test_case('.', Ok(ManifoldSlot::Empty))]
+    #[test_case('^', Ok(ManifoldSlot::Splitter(Status::Activated)); "explicit payload")]
+    #[test_case('v', Ok(ManifoldSlot::Reflector(Status::Deactivated)); "default payload")]
+    #[test_case('S', Err(BadTileTypeError('S')); "unknown")]
+    fn test_derived_try_from(inp: char, exp: Result<ManifoldSlot, BadTileTypeError>) {
+        assert_eq!(ManifoldSlot::try_from(inp), exp);
+    }
+
+    #[test]
+    fn test_derived_chars_and_display() {
+        assert_eq!(ManifoldSlot::valid_chars(), vec!['.', '^', 'v']);
+        for c in ManifoldSlot::valid_chars() {
+            assert_eq!(
+                ManifoldSlot::try_from(c).unwrap().to_string(),
+                c.to_string()
+            );
+        }
+        assert_eq!(ManifoldSlot::Splitter(Status::Deactivated).to_string(), "^");
+    }
+
     #[test]
     fn test_parse_collection() {
         let inp = include_str!("./data/lava_sample.txt");
         let actual: Collection<LavaTile> = parse_collection(inp).unwrap().1;
-        assert_eq!(actual.0.len(), 63);
+        assert_eq!(actual.len(), 63);
     }
 
     #[test]
@@ -348,8 +550,119 @@ mod tests {
         assert!(actual.is_ok());
         let unwrapped = actual.unwrap();
         assert_eq!(unwrapped.0, "");
-        assert_eq!(unwrapped.1[0].0.len(), 63);
-        assert_eq!(unwrapped.1[1].0.len(), 63);
+        assert_eq!(unwrapped.1[0].len(), 63);
+        assert_eq!(unwrapped.1[1].len(), 63);
+    }
+
+    #[test]
+    fn test_collection_dimensions() {
+        let inp = include_str!("./data/lava_sample.txt");
+        let actual: Collection<LavaTile> = parse_collection(inp).unwrap().1;
+        assert_eq!(actual.count_rows(), 7);
+        assert_eq!(actual.count_columns(), 9);
+    }
+
+    #[test_case(Loc::new(0, 0), Some(LavaTile::Rocks))]
+    #[test_case(Loc::new(1, 0), Some(LavaTile::Ash))]
+    #[test_case(Loc::new(8, 2), Some(LavaTile::Rocks))]
+    #[test_case(Loc::new(9, 2), None; "past the right edge")]
+    #[test_case(Loc::new(0, 7), None; "past the bottom edge")]
+    #[test_case(Loc::new(-1, 0), None; "negative")]
+    fn test_get_tile(loc: Loc, exp: Option<LavaTile>) {
+        let inp = include_str!("./data/lava_sample.txt");
+        let actual: Collection<LavaTile> = parse_collection(inp).unwrap().1;
+        let tile = actual.get_tile(loc);
+        assert_eq!(tile.map(|t| *t.get_type()), exp);
+        assert!(tile.is_none_or(|t| t.loc() == &loc));
+    }
+
+    #[test]
+    fn test_get_tile_mut() {
+        let inp = include_str!("./data/lava_sample.txt");
+        let mut actual: Collection<LavaTile> = parse_collection(inp).unwrap().1;
+        actual
+            .get_tile_mut(Loc::new(1, 0))
+            .unwrap()
+            .set_type(LavaTile::Rocks);
+        assert_eq!(actual.get_row(0).to_string(), "####..##.");
+        assert_eq!(actual.count_tile_type(&LavaTile::Rocks), 29);
+    }
+
+    #[test]
+    fn test_get_row_and_column() {
+        let inp = include_str!("./data/lava_sample.txt");
+        let actual: Collection<LavaTile> = parse_collection(inp).unwrap().1;
+        assert_eq!(actual.get_row(2).to_string(), "##......#");
+        assert_eq!(actual.get_column(0).to_string(), "#.##..#");
+        assert_eq!(actual.row_slice(7).len(), 0);
+        assert_eq!(actual.column_iter(9).count(), 0);
+        assert_eq!(actual.rows().count(), 7);
+    }
+
+    #[test]
+    fn test_parse_collection_rejects_ragged_rows() {
+        let actual = parse_collection::<LavaTile>("#.#\n##\n");
+        assert!(actual.is_err());
+    }
+
+    #[test_case(Loc::new(0, 0), Neighborhood::Cardinal, vec![Direction::East, Direction::South]; "cardinal corner")]
+    #[test_case(Loc::new(4, 3), Neighborhood::Cardinal, Direction::get_cardinal(); "cardinal middle")]
+    #[test_case(Loc::new(0, 0), Neighborhood::All, vec![Direction::East, Direction::South, Direction::SouthEast]; "all corner")]
+    #[test_case(Loc::new(4, 3), Neighborhood::All, Direction::get_all(); "all middle")]
+    fn test_neighbors(loc: Loc, neighborhood: Neighborhood, exp: Vec<Direction>) {
+        let inp = include_str!("./data/lava_sample.txt");
+        let collection: Collection<LavaTile> = parse_collection(inp).unwrap().1;
+        let actual: Vec<Offset> = collection
+            .neighbors(loc, &neighborhood)
+            .into_iter()
+            .map(|(offset, _)| offset)
+            .collect();
+        assert_eq!(
+            actual,
+            exp.iter().map(Direction::to_offset).collect::<Vec<_>>()
+        );
+    }
+
+    #[test_case(Loc::new(8, 6), vec![(0, 0), (-2, -1), (1, 2)], vec![(0, 0), (-2, -1)]; "keeps the tile itself")]
+    #[test_case(Loc::new(4, 3), vec![(1, 0), (2, 0), (-3, 1)], vec![(1, 0), (2, 0), (-3, 1)]; "same direction")]
+    fn test_stencil_neighbors(loc: Loc, stencil: Vec<(isize, isize)>, exp: Vec<(isize, isize)>) {
+        let inp = include_str!("./data/lava_sample.txt");
+        let collection: Collection<LavaTile> = parse_collection(inp).unwrap().1;
+        let actual: Vec<Loc> = collection
+            .neighbors(loc, &Neighborhood::Stencil(stencil))
+            .into_iter()
+            .map(|(offset, t)| {
+                assert_eq!(*t.loc(), loc + offset);
+                *t.loc()
+            })
+            .collect();
+        let exp: Vec<Loc> = exp.into_iter().map(|o| loc + Offset::from(o)).collect();
+        assert_eq!(actual, exp);
+    }
+
+    #[test]
+    fn test_neighbors_by_direction() {
+        let inp = include_str!("./data/lava_sample.txt");
+        let collection: Collection<LavaTile> = parse_collection(inp).unwrap().1;
+        let stencil = Neighborhood::Stencil(vec![(0, 0), (-2, -1), (-1, 0), (1, 2)]);
+        let actual: Vec<Direction> = collection
+            .neighbors_by_direction(Loc::new(8, 6), &stencil)
+            .into_iter()
+            .map(|(d, _)| d)
+            .collect();
+        assert_eq!(actual, vec![Direction::NorthWest, Direction::West]);
+    }
+
+    #[test_case(Loc::new(0, 0), Neighborhood::All, 0)]
+    #[test_case(Loc::new(1, 2), Neighborhood::All, 4)]
+    #[test_case(Loc::new(1, 2), Neighborhood::Cardinal, 2)]
+    #[test_case(Loc::new(0, 0), Neighborhood::Stencil(vec![(0, 0), (1, 0)]), 1; "stencil counts the tile itself")]
+    fn test_count_neighbors_matching(loc: Loc, neighborhood: Neighborhood, exp: usize) {
+        let inp = include_str!("./data/lava_sample.txt");
+        let collection: Collection<LavaTile> = parse_collection(inp).unwrap().1;
+        let actual = collection
+            .count_neighbors_matching(loc, &neighborhood, |t| t.get_type() == &LavaTile::Rocks);
+        assert_eq!(actual, exp);
     }
 
     #[test_case((Loc::new(1,1), Loc::new(5, 5)), vec![Loc::new(2, 2), Loc::new(3, 3), Loc::new(4, 4)])]
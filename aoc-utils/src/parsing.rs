@@ -0,0 +1,360 @@
+use crate::{Collection, CollectionGroup, Loc, ParseableCharacters};
+use itertools::Itertools;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// Where and why a grid failed to parse. Lines and columns are 1-based, and
+/// `found` is `None` when the input ended early.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    line: usize,
+    column: usize,
+    found: Option<char>,
+    expected: Vec<char>,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, found: Option<char>, expected: Vec<char>) -> Self {
+        Self {
+            line,
+            column,
+            found,
+            expected,
+        }
+    }
+    pub fn line(&self) -> usize {
+        self.line
+    }
+    pub fn column(&self) -> usize {
+        self.column
+    }
+    pub fn found(&self) -> Option<char> {
+        self.found
+    }
+    pub fn expected(&self) -> &[char] {
+        &self.expected
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let found = match self.found {
+            Some(c) => format!("{c:?}"),
+            None => "end of input".into(),
+        };
+        let expected = self.expected.iter().map(|c| format!("{c:?}")).join(", ");
+        write!(
+            f,
+            "line {}, column {}: found {found}, expected one of [{expected}]",
+            self.line, self.column
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses a single grid, stopping at the first blank line, and reports the
+/// first bad character, ragged row or missing newline it runs into.
+pub fn try_parse_collection<T>(inp: &str) -> Result<Collection<T>, ParseError>
+where
+    T: ParseableCharacters + TryFrom<char> + Copy + PartialEq,
+{
+    let lines = inp
+        .split_inclusive('\n')
+        .take_while(|l| *l != "\n")
+        .collect_vec();
+    if lines.is_empty() {
+        return Err(ParseError::new(1, 1, inp.chars().next(), T::valid_chars()));
+    }
+    parse_lines(&lines, 1)
+}
+
+/// Parses blank-line separated grids, reporting errors against the whole input.
+pub fn try_parse_collection_group<T>(inp: &str) -> Result<CollectionGroup<T>, ParseError>
+where
+    T: ParseableCharacters + TryFrom<char> + Copy + PartialEq,
+{
+    let lines = inp.split_inclusive('\n').collect_vec();
+    let mut group = vec![];
+    let mut first_line = 1;
+    for block in lines.split(|l| *l == "\n") {
+        if !block.is_empty() {
+            group.push(parse_lines(block, first_line)?);
+        }
+        first_line += block.len() + 1;
+    }
+    if group.is_empty() {
+        return Err(ParseError::new(1, 1, inp.chars().next(), T::valid_chars()));
+    }
+    Ok(group)
+}
+
+fn parse_lines<T>(lines: &[&str], first_line: usize) -> Result<Collection<T>, ParseError>
+where
+    T: ParseableCharacters + TryFrom<char> + Copy + PartialEq,
+{
+    let valid_chars = T::valid_chars();
+    let mut rows: Vec<Vec<T>> = vec![];
+    for (line, raw) in (first_line..).zip(lines) {
+        let width = rows.first().map(Vec::len);
+        let mut row = vec![];
+        for (column, c) in (1..).zip(raw.chars()) {
+            let row_complete = width.is_some_and(|w| row.len() == w);
+            if c == '\n' && (row_complete || width.is_none()) {
+                break;
+            }
+            if row_complete {
+                return Err(ParseError::new(line, column, Some(c), vec!['\n']));
+            }
+            let tile = valid_chars
+                .contains(&c)
+                .then(|| T::try_from(c).ok())
+                .flatten()
+                .ok_or_else(|| ParseError::new(line, column, Some(c), valid_chars.clone()))?;
+            row.push(tile);
+        }
+        if !raw.ends_with('\n') {
+            let expected = if width.is_some_and(|w| row.len() < w) {
+                valid_chars
+            } else {
+                vec!['\n']
+            };
+            return Err(ParseError::new(line, row.len() + 1, None, expected));
+        }
+        rows.push(row);
+    }
+    Ok(Collection::from_rows(rows).unwrap())
+}
+
+impl<T> Collection<T>
+where
+    T: ParseableCharacters + Copy + TryFrom<char> + PartialEq,
+{
+    pub fn try_from_puzzle_input(puzzle_input: &str) -> Result<Collection<T>, ParseError> {
+        try_parse_collection(puzzle_input)
+    }
+}
+
+/// A forgiving grid parser. Unlike `try_parse_collection` it accepts `\r\n`
+/// line endings and a missing final newline, and can be told to pad ragged
+/// rows or to pick out marker characters such as a start position.
+#[derive(Debug, Clone)]
+pub struct GridParser<T> {
+    fill: Option<T>,
+    markers: Vec<(char, T)>,
+}
+
+impl<T> Default for GridParser<T> {
+    fn default() -> Self {
+        Self {
+            fill: None,
+            markers: vec![],
+        }
+    }
+}
+
+impl<T> GridParser<T>
+where
+    T: ParseableCharacters + TryFrom<char> + Copy + PartialEq,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pads rows shorter than the longest one with `fill` instead of rejecting them.
+    pub fn pad_with(mut self, fill: T) -> Self {
+        self.fill = Some(fill);
+        self
+    }
+
+    /// Records every location of `marker` and stores `base` there in its place.
+    pub fn marker(mut self, marker: char, base: T) -> Self {
+        self.markers.push((marker, base));
+        self
+    }
+
+    /// Parses the first grid in `inp`, stopping at the first blank line.
+    pub fn parse(&self, inp: &str) -> Result<ParsedGrid<T>, ParseError> {
+        let valid_chars = T::valid_chars()
+            .into_iter()
+            .chain(self.markers.iter().map(|(c, _)| *c))
+            .collect_vec();
+        let lines = inp.lines().take_while(|l| !l.is_empty()).collect_vec();
+        if lines.is_empty() {
+            return Err(ParseError::new(1, 1, inp.chars().next(), valid_chars));
+        }
+
+        let mut markers: HashMap<char, Vec<Loc>> = HashMap::new();
+        let mut rows: Vec<Vec<T>> = vec![];
+        for (y, raw) in lines.iter().enumerate() {
+            let mut row = vec![];
+            for (x, c) in raw.chars().enumerate() {
+                let tile = match self.markers.iter().find(|(m, _)| *m == c) {
+                    Some((_, base)) => {
+                        markers
+                            .entry(c)
+                            .or_default()
+                            .push(Loc::new(x as isize, y as isize));
+                        Some(*base)
+                    }
+                    None => T::valid_chars()
+                        .contains(&c)
+                        .then(|| T::try_from(c).ok())
+                        .flatten(),
+                };
+                let tile = tile
+                    .ok_or_else(|| ParseError::new(y + 1, x + 1, Some(c), valid_chars.clone()))?;
+                row.push(tile);
+            }
+            rows.push(row);
+        }
+
+        let width = match self.fill {
+            Some(_) => rows.iter().map(Vec::len).max().unwrap_or_default(),
+            None => rows[0].len(),
+        };
+        for (line, (raw, row)) in (1..).zip(lines.iter().zip(rows.iter_mut())) {
+            match (row.len().cmp(&width), self.fill) {
+                (Ordering::Less, Some(fill)) => row.resize(width, fill),
+                (Ordering::Less, None) => {
+                    return Err(ParseError::new(
+                        line,
+                        row.len() + 1,
+                        Some('\n'),
+                        valid_chars,
+                    ))
+                }
+                (Ordering::Greater, _) => {
+                    let found = raw.chars().nth(width);
+                    return Err(ParseError::new(line, width + 1, found, vec!['\n']));
+                }
+                (Ordering::Equal, _) => {}
+            }
+        }
+        Ok(ParsedGrid {
+            collection: Collection::from_rows(rows).unwrap(),
+            markers,
+        })
+    }
+}
+/// A grid returned by `GridParser`, along with where each marker was found.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedGrid<T> {
+    collection: Collection<T>,
+    markers: HashMap<char, Vec<Loc>>,
+}
+
+impl<T> ParsedGrid<T> {
+    pub fn collection(&self) -> &Collection<T> {
+        &self.collection
+    }
+    pub fn into_collection(self) -> Collection<T> {
+        self.collection
+    }
+    /// Every location of `marker`, in reading order.
+    pub fn markers(&self, marker: char) -> &[Loc] {
+        self.markers.get(&marker).map_or(&[], Vec::as_slice)
+    }
+    /// The first location of `marker`, for markers that appear once.
+    pub fn marker(&self, marker: char) -> Option<Loc> {
+        self.markers(marker).first().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::LavaTile;
+    use test_case::test_case;
+
+    #[test]
+    fn test_try_parse_collection() {
+        let actual = try_parse_collection::<LavaTile>("#.#\n.#.\n\n###\n").unwrap();
+        assert_eq!(actual.count_rows(), 2);
+        assert_eq!(actual.count_columns(), 3);
+    }
+
+    #[test_case("#.#\n.x.\n", ParseError::new(2, 2, Some('x'), vec!['.', '#']); "bad character")]
+    #[test_case("#.#\n.#.", ParseError::new(2, 4, None, vec!['\n']); "missing newline")]
+    #[test_case("#.#\n.#\n", ParseError::new(2, 3, Some('\n'), vec!['.', '#']); "short row")]
+    #[test_case("#.#\n.#.#\n", ParseError::new(2, 4, Some('#'), vec!['\n']); "long row")]
+    #[test_case("#.#\n.#", ParseError::new(2, 3, None, vec!['.', '#']); "short row without newline")]
+    #[test_case("", ParseError::new(1, 1, None, vec!['.', '#']); "empty")]
+    #[test_case("#.#\r\n", ParseError::new(1, 4, Some('\r'), vec!['.', '#']); "carriage return")]
+    fn test_try_parse_collection_errors(inp: &str, exp: ParseError) {
+        let actual = Collection::<LavaTile>::try_from_puzzle_input(inp);
+        assert_eq!(actual, Err(exp));
+    }
+
+    #[test]
+    fn test_try_parse_collection_group() {
+        let inp = include_str!("./data/lava_sample.txt");
+        let actual = try_parse_collection_group::<LavaTile>(inp).unwrap();
+        assert_eq!(actual.len(), 2);
+        assert_eq!(actual[1].len(), 63);
+    }
+
+    #[test]
+    fn test_try_parse_collection_group_reports_absolute_line() {
+        let actual = try_parse_collection_group::<LavaTile>("#.\n.#\n\n##\n#?\n");
+        assert_eq!(
+            actual,
+            Err(ParseError::new(5, 2, Some('?'), vec!['.', '#']))
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let err = ParseError::new(3, 7, Some('x'), vec!['#', '.']);
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 7: found 'x', expected one of ['#', '.']"
+        );
+        let err = ParseError::new(1, 4, None, vec!['\n']);
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 4: found end of input, expected one of ['\\n']"
+        );
+    }
+
+    #[test_case("#.#\r\n.#.\r\n"; "crlf")]
+    #[test_case("#.#\n.#."; "missing newline")]
+    #[test_case("#.#\r\n.#.\n\n###\n"; "stops at blank line")]
+    fn test_grid_parser_line_endings(inp: &str) {
+        let actual = GridParser::<LavaTile>::new().parse(inp).unwrap();
+        assert_eq!(actual.collection().to_string(), "#.#\n.#.\n");
+    }
+
+    #[test]
+    fn test_grid_parser_pads_ragged_rows() {
+        let actual = GridParser::new()
+            .pad_with(LavaTile::Ash)
+            .parse("#\n.##\n##\n")
+            .unwrap();
+        assert_eq!(actual.collection().to_string(), "#..\n.##\n##.\n");
+    }
+
+    #[test_case("#.#\n.#\n", ParseError::new(2, 3, Some('\n'), vec!['.', '#', 'S']); "short row")]
+    #[test_case("#.#\n.#.#\n", ParseError::new(2, 4, Some('#'), vec!['\n']); "long row")]
+    #[test_case("#.#\n.E.\n", ParseError::new(2, 2, Some('E'), vec!['.', '#', 'S']); "unknown marker")]
+    #[test_case("\n", ParseError::new(1, 1, Some('\n'), vec!['.', '#', 'S']); "blank")]
+    fn test_grid_parser_errors(inp: &str, exp: ParseError) {
+        let actual = GridParser::new().marker('S', LavaTile::Ash).parse(inp);
+        assert_eq!(actual, Err(exp));
+    }
+
+    #[test]
+    fn test_grid_parser_markers() {
+        let actual = GridParser::new()
+            .marker('S', LavaTile::Ash)
+            .marker('E', LavaTile::Rocks)
+            .parse("S.#\r\n#E.\r\n..S")
+            .unwrap();
+        assert_eq!(actual.marker('S'), Some(Loc::new(0, 0)));
+        assert_eq!(actual.markers('S'), &[Loc::new(0, 0), Loc::new(2, 2)]);
+        assert_eq!(actual.marker('E'), Some(Loc::new(1, 1)));
+        assert_eq!(actual.marker('X'), None);
+        assert_eq!(actual.into_collection().to_string(), "..#\n##.\n...\n");
+    }
+}
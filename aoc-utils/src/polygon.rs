@@ -0,0 +1,327 @@
+use crate::{Loc, Rect};
+use num::Integer;
+
+/// Where a point sits relative to a polygon.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Containment {
+    Inside,
+    OnEdge,
+    Outside,
+}
+
+/// A simple polygon on the integer grid. The last vertex joins back up with
+/// the first, so the vertex list should not repeat the starting point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<Loc>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Loc>) -> Self {
+        Self { vertices }
+    }
+    pub fn vertices(&self) -> &[Loc] {
+        &self.vertices
+    }
+    /// Every edge as a pair of vertices, including the one that closes the loop.
+    pub fn edges(&self) -> impl Iterator<Item = (Loc, Loc)> + '_ {
+        self.vertices
+            .iter()
+            .copied()
+            .zip(self.vertices.iter().copied().cycle().skip(1))
+    }
+
+    /// Twice the enclosed area, by the shoelace formula. Always a whole number
+    /// for grid vertices, unlike the area itself.
+    pub fn double_area(&self) -> usize {
+        self.edges()
+            .map(|(a, b)| a.x * b.y - b.x * a.y)
+            .sum::<isize>()
+            .unsigned_abs()
+    }
+    pub fn area(&self) -> f64 {
+        self.double_area() as f64 / 2.0
+    }
+    /// How many grid points lie on the edges.
+    pub fn boundary_points(&self) -> usize {
+        self.edges()
+            .map(|(a, b)| (b.x - a.x).gcd(&(b.y - a.y)).unsigned_abs())
+            .sum()
+    }
+    /// How many grid points lie strictly inside, by Pick's theorem. A polygon
+    /// with no area, such as one that doubles back along a line, has none.
+    pub fn interior_points(&self) -> usize {
+        match self.double_area() {
+            0 => 0,
+            double_area => (double_area + 2 - self.boundary_points()) / 2,
+        }
+    }
+
+    /// Whether every edge is horizontal or vertical, with turns at every vertex.
+    pub fn is_rectilinear(&self) -> bool {
+        let edges: Vec<(Loc, Loc)> = self.edges().collect();
+        self.vertices.len() >= 4
+            && edges.iter().all(|(a, b)| (a.x == b.x) != (a.y == b.y))
+            && edges
+                .iter()
+                .zip(edges.iter().cycle().skip(1))
+                .all(|((a, b), (_, c))| (a.x == b.x) != (b.x == c.x))
+    }
+
+    pub fn locate(&self, loc: Loc) -> Containment {
+        self.locate_point(loc.x as f64, loc.y as f64)
+    }
+    /// True for points inside or on an edge.
+    pub fn contains(&self, loc: Loc) -> bool {
+        self.locate(loc) != Containment::Outside
+    }
+
+    /// Checks for an edge through the point, then casts a ray to the right and
+    /// counts the edges it crosses. Each edge counts as covering its lower end
+    /// but not its upper one, so a ray through a vertex is counted once.
+    fn locate_point(&self, x: f64, y: f64) -> Containment {
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            let (ax, ay, bx, by) = (a.x as f64, a.y as f64, b.x as f64, b.y as f64);
+            let cross = (bx - ax) * (y - ay) - (by - ay) * (x - ax);
+            let within = ax.min(bx) <= x && x <= ax.max(bx) && ay.min(by) <= y && y <= ay.max(by);
+            if cross == 0.0 && within {
+                return Containment::OnEdge;
+            }
+            if (ay > y) != (by > y) && x < ax + (y - ay) * (bx - ax) / (by - ay) {
+                inside = !inside;
+            }
+        }
+        if inside {
+            Containment::Inside
+        } else {
+            Containment::Outside
+        }
+    }
+
+    /// Whether any edge touches the segment from `start` to `end`.
+    pub fn intersects_segment(&self, start: Loc, end: Loc) -> bool {
+        self.edges()
+            .any(|(a, b)| segments_intersect((start, end), (a, b)))
+    }
+
+    /// Whether every point of the segment is inside or on the polygon. The
+    /// segment is cut wherever it meets an edge, and since no piece can cross
+    /// the boundary, checking the middle of each piece settles it.
+    pub fn contains_segment(&self, start: Loc, end: Loc) -> bool {
+        let mut cuts = vec![0.0, 1.0];
+        for (a, b) in self.edges() {
+            cuts.extend(crossing_params((start, end), (a, b)));
+        }
+        cuts.sort_by(f64::total_cmp);
+        let (dx, dy) = ((end.x - start.x) as f64, (end.y - start.y) as f64);
+        self.contains(start)
+            && self.contains(end)
+            && cuts.windows(2).all(|w| {
+                let t = (w[0] + w[1]) / 2.0;
+                self.locate_point(start.x as f64 + t * dx, start.y as f64 + t * dy)
+                    != Containment::Outside
+            })
+    }
+
+    /// Whether the whole rectangle is inside or on the polygon. A simple
+    /// polygon holds everything its outline does, so only the rectangle's four
+    /// sides need checking.
+    pub fn contains_rect(&self, rect: &Rect) -> bool {
+        let (min, max) = (rect.min(), rect.max());
+        let corners = [min, Loc::new(max.x, min.y), max, Loc::new(min.x, max.y)];
+        corners
+            .iter()
+            .zip(corners.iter().cycle().skip(1))
+            .all(|(a, b)| self.contains_segment(*a, *b))
+    }
+}
+
+impl From<Vec<Loc>> for Polygon {
+    fn from(value: Vec<Loc>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl FromIterator<Loc> for Polygon {
+    fn from_iter<I: IntoIterator<Item = Loc>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+fn cross(o: Loc, a: Loc, b: Loc) -> isize {
+    (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)
+}
+
+fn on_segment(loc: Loc, (a, b): (Loc, Loc)) -> bool {
+    cross(a, b, loc) == 0
+        && a.x.min(b.x) <= loc.x
+        && loc.x <= a.x.max(b.x)
+        && a.y.min(b.y) <= loc.y
+        && loc.y <= a.y.max(b.y)
+}
+
+/// Whether two closed segments share at least one point, including touching
+/// ends and collinear overlaps.
+pub fn segments_intersect(first: (Loc, Loc), second: (Loc, Loc)) -> bool {
+    let (a, b) = first;
+    let (c, d) = second;
+    let (d1, d2) = (cross(a, b, c).signum(), cross(a, b, d).signum());
+    let (d3, d4) = (cross(c, d, a).signum(), cross(c, d, b).signum());
+    if d1 * d2 < 0 && d3 * d4 < 0 {
+        return true;
+    }
+    on_segment(c, first) || on_segment(d, first) || on_segment(a, second) || on_segment(b, second)
+}
+
+/// How far along `segment`, from 0 at its start to 1 at its end, it meets
+/// `edge`. A collinear overlap gives both ends of the shared stretch.
+fn crossing_params(segment: (Loc, Loc), edge: (Loc, Loc)) -> Vec<f64> {
+    let (start, end) = segment;
+    let (dx, dy) = ((end.x - start.x) as f64, (end.y - start.y) as f64);
+    let length = dx * dx + dy * dy;
+    if length == 0.0 || !segments_intersect(segment, edge) {
+        return vec![];
+    }
+    let along = |loc: Loc| ((loc.x - start.x) as f64 * dx + (loc.y - start.y) as f64 * dy) / length;
+    let (c, d) = edge;
+    if cross(start, end, c) == 0 && cross(start, end, d) == 0 {
+        return [along(c), along(d)]
+            .into_iter()
+            .map(|t| t.clamp(0.0, 1.0))
+            .collect();
+    }
+    let (ex, ey) = ((d.x - c.x) as f64, (d.y - c.y) as f64);
+    let denominator = dx * ey - dy * ex;
+    let t = ((c.x - start.x) as f64 * ey - (c.y - start.y) as f64 * ex) / denominator;
+    vec![t.clamp(0.0, 1.0)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn polygon(points: &[(isize, isize)]) -> Polygon {
+        points.iter().map(|p| Loc::from(*p)).collect()
+    }
+
+    fn red_tiles() -> Polygon {
+        polygon(&[
+            (7, 1),
+            (11, 1),
+            (11, 7),
+            (9, 7),
+            (9, 5),
+            (2, 5),
+            (2, 3),
+            (7, 3),
+        ])
+    }
+
+    fn u_shape() -> Polygon {
+        polygon(&[
+            (0, 0),
+            (10, 0),
+            (10, 10),
+            (6, 10),
+            (6, 2),
+            (4, 2),
+            (4, 10),
+            (0, 10),
+        ])
+    }
+
+    #[test_case(polygon(&[(0, 0), (4, 0), (4, 4), (0, 4)]), 32, 16, 9; "square")]
+    #[test_case(polygon(&[(0, 0), (4, 0), (0, 4)]), 16, 12, 3; "triangle")]
+    #[test_case(polygon(&[(0, 4), (4, 4), (4, 0), (0, 0)]), 32, 16, 9; "clockwise")]
+    #[test_case(red_tiles(), 60, 30, 16; "tile loop")]
+    #[test_case(polygon(&[(0, 0), (4, 0)]), 0, 8, 0; "segment")]
+    #[test_case(polygon(&[(2, 3)]), 0, 0, 0; "point")]
+    #[test_case(polygon(&[]), 0, 0, 0; "empty")]
+    fn test_area(poly: Polygon, double_area: usize, boundary: usize, interior: usize) {
+        assert_eq!(poly.double_area(), double_area);
+        assert_eq!(poly.area(), double_area as f64 / 2.0);
+        assert_eq!(poly.boundary_points(), boundary);
+        assert_eq!(poly.interior_points(), interior);
+    }
+
+    #[test]
+    fn test_pick_matches_counting() {
+        let poly = u_shape();
+        let counted = (-1..=11)
+            .flat_map(|x| (-1..=11).map(move |y| Loc::new(x, y)))
+            .fold((0, 0), |(inside, edge), loc| match poly.locate(loc) {
+                Containment::Inside => (inside + 1, edge),
+                Containment::OnEdge => (inside, edge + 1),
+                Containment::Outside => (inside, edge),
+            });
+        assert_eq!(counted, (poly.interior_points(), poly.boundary_points()));
+    }
+
+    #[test_case(Loc::new(8, 2), Containment::Inside)]
+    #[test_case(Loc::new(3, 4), Containment::Inside)]
+    #[test_case(Loc::new(7, 1), Containment::OnEdge; "vertex")]
+    #[test_case(Loc::new(11, 4), Containment::OnEdge; "edge")]
+    #[test_case(Loc::new(2, 1), Containment::Outside; "level with a vertex")]
+    #[test_case(Loc::new(5, 7), Containment::Outside)]
+    #[test_case(Loc::new(0, 5), Containment::Outside; "ray along an edge")]
+    fn test_locate(loc: Loc, exp: Containment) {
+        assert_eq!(red_tiles().locate(loc), exp);
+    }
+
+    #[test_case((9, 5), (2, 3), true)]
+    #[test_case((7, 3), (11, 1), true)]
+    #[test_case((2, 5), (11, 1), false)]
+    #[test_case((7, 1), (2, 5), false)]
+    #[test_case((9, 7), (9, 5), true; "along an edge")]
+    fn test_contains_rect_red_tiles(a: (isize, isize), b: (isize, isize), exp: bool) {
+        let rect = Rect::new(Loc::from(a), Loc::from(b));
+        assert_eq!(red_tiles().contains_rect(&rect), exp);
+    }
+
+    #[test_case((0, 0), (4, 10), true; "left arm")]
+    #[test_case((0, 0), (10, 2), true; "top band")]
+    #[test_case((0, 0), (10, 3), false; "into the notch")]
+    #[test_case((0, 10), (10, 10), false; "across the notch mouth")]
+    #[test_case((4, 2), (6, 2), true; "along the notch top")]
+    #[test_case((5, 5), (5, 5), false; "point in the notch")]
+    fn test_contains_rect_u_shape(a: (isize, isize), b: (isize, isize), exp: bool) {
+        let rect = Rect::new(Loc::from(a), Loc::from(b));
+        assert_eq!(u_shape().contains_rect(&rect), exp);
+    }
+
+    #[test]
+    fn test_is_rectilinear() {
+        assert!(red_tiles().is_rectilinear());
+        assert!(u_shape().is_rectilinear());
+        assert!(!polygon(&[(0, 0), (4, 0), (0, 4)]).is_rectilinear());
+        assert!(!polygon(&[(0, 0), (2, 0), (4, 0), (4, 4), (0, 4)]).is_rectilinear());
+        assert!(!polygon(&[(0, 0), (4, 0)]).is_rectilinear());
+    }
+
+    #[test_case((0, 0), (4, 4), (0, 4), (4, 0), true; "crossing")]
+    #[test_case((0, 0), (4, 0), (4, 0), (4, 4), true; "touching ends")]
+    #[test_case((0, 0), (4, 0), (2, 0), (6, 0), true; "collinear overlap")]
+    #[test_case((0, 0), (4, 0), (5, 0), (6, 0), false; "collinear apart")]
+    #[test_case((0, 0), (4, 0), (2, 1), (2, 3), false; "apart")]
+    #[test_case((0, 0), (4, 4), (2, 2), (2, 6), true; "t junction")]
+    fn test_segments_intersect(
+        a: (isize, isize),
+        b: (isize, isize),
+        c: (isize, isize),
+        d: (isize, isize),
+        exp: bool,
+    ) {
+        let (a, b, c, d) = (Loc::from(a), Loc::from(b), Loc::from(c), Loc::from(d));
+        assert_eq!(segments_intersect((a, b), (c, d)), exp);
+        assert_eq!(segments_intersect((c, d), (a, b)), exp);
+    }
+
+    #[test]
+    fn test_intersects_segment() {
+        let poly = u_shape();
+        assert!(poly.intersects_segment(Loc::new(5, 5), Loc::new(5, 0)));
+        assert!(!poly.intersects_segment(Loc::new(5, 5), Loc::new(5, 9)));
+    }
+}
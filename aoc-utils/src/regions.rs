@@ -0,0 +1,311 @@
+use crate::search::bfs;
+use crate::{Collection, Loc, Neighborhood, ParseableCharacters, Rect, Tile};
+use std::collections::HashSet;
+use std::fmt::Display;
+
+const LABEL_CHARS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// A connected set of locations. Perimeter and sides are always measured
+/// along cardinal edges, whatever neighborhood was used to connect the region.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    locs: Vec<Loc>,
+    members: HashSet<Loc>,
+}
+
+impl Region {
+    fn new(locs: impl IntoIterator<Item = Loc>) -> Self {
+        let members: HashSet<Loc> = locs.into_iter().collect();
+        let mut locs: Vec<Loc> = members.iter().copied().collect();
+        locs.sort_by_key(|l| (l.y, l.x));
+        Self { locs, members }
+    }
+    pub fn locs(&self) -> &[Loc] {
+        &self.locs
+    }
+    pub fn contains(&self, loc: &Loc) -> bool {
+        self.members.contains(loc)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.locs.is_empty()
+    }
+    pub fn area(&self) -> usize {
+        self.locs.len()
+    }
+    pub fn perimeter(&self) -> usize {
+        self.locs
+            .iter()
+            .map(|loc| {
+                [(0, -1), (1, 0), (0, 1), (-1, 0)]
+                    .iter()
+                    .filter(|(dx, dy)| !self.contains(&Loc::new(loc.x + dx, loc.y + dy)))
+                    .count()
+            })
+            .sum()
+    }
+    /// The number of straight edges around the region, counted as its corners.
+    pub fn sides(&self) -> usize {
+        self.locs
+            .iter()
+            .map(|loc| {
+                [(1, -1), (1, 1), (-1, 1), (-1, -1)]
+                    .iter()
+                    .filter(|(dx, dy)| {
+                        let horizontal = self.contains(&Loc::new(loc.x + dx, loc.y));
+                        let vertical = self.contains(&Loc::new(loc.x, loc.y + dy));
+                        let diagonal = self.contains(&Loc::new(loc.x + dx, loc.y + dy));
+                        (!horizontal && !vertical) || (horizontal && vertical && !diagonal)
+                    })
+                    .count()
+            })
+            .sum()
+    }
+    pub fn bounding_box(&self) -> Option<Rect> {
+        Rect::bounding(self.locs.iter().copied())
+    }
+}
+
+/// A tile for label maps: each region renders as its own character. There are
+/// only 62 of those, so any later region renders as `?` rather than reusing one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Label {
+    Unlabeled,
+    Region(usize),
+}
+impl ParseableCharacters for Label {
+    fn valid_chars() -> Vec<char> {
+        let mut chars: Vec<char> = LABEL_CHARS.chars().collect();
+        chars.push('.');
+        chars
+    }
+}
+impl TryFrom<char> for Label {
+    type Error = crate::BadTileTypeError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        if value == '.' {
+            return Ok(Self::Unlabeled);
+        }
+        LABEL_CHARS
+            .chars()
+            .position(|c| c == value)
+            .map(Self::Region)
+            .ok_or(crate::BadTileTypeError(value))
+    }
+}
+impl Display for Label {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Label::Unlabeled => f.write_str("."),
+            Label::Region(idx) => {
+                let c = LABEL_CHARS.chars().nth(*idx).unwrap_or('?');
+                write!(f, "{c}")
+            }
+        }
+    }
+}
+
+/// The regions of a grid along with a same-sized map of which region each tile belongs to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Components {
+    regions: Vec<Region>,
+    label_map: Collection<Label>,
+}
+impl Components {
+    pub fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+    pub fn label_map(&self) -> &Collection<Label> {
+        &self.label_map
+    }
+    pub fn region_of(&self, loc: Loc) -> Option<&Region> {
+        match self.label_map.get_tile(loc)?.get_type() {
+            Label::Region(idx) => self.regions.get(*idx),
+            Label::Unlabeled => None,
+        }
+    }
+}
+
+impl<T> Collection<T>
+where
+    T: ParseableCharacters + Copy + TryFrom<char> + PartialEq,
+{
+    /// Every location reachable from `start` through tiles that satisfy `predicate`.
+    pub fn flood_fill<P>(&self, start: Loc, predicate: P, neighborhood: &Neighborhood) -> Region
+    where
+        P: Fn(&Tile<T>) -> bool,
+    {
+        if !self.get_tile(start).is_some_and(&predicate) {
+            return Region::new([]);
+        }
+        let reached = bfs([start], |loc| {
+            self.neighbors(*loc, neighborhood)
+                .into_iter()
+                .filter(|(_, t)| predicate(t))
+                .map(|(_, t)| *t.loc())
+                .collect::<Vec<Loc>>()
+        });
+        Region::new(reached.distances().keys().copied())
+    }
+
+    /// Connected regions of the tiles that satisfy `predicate`.
+    pub fn components<P>(&self, neighborhood: &Neighborhood, predicate: P) -> Components
+    where
+        P: Fn(&Tile<T>) -> bool,
+    {
+        self.label_regions(|seed| self.flood_fill(seed, &predicate, neighborhood))
+    }
+
+    /// Connected regions of adjacent tiles sharing the same type, covering the whole grid.
+    pub fn components_by_type(&self, neighborhood: &Neighborhood) -> Components {
+        self.label_regions(|seed| {
+            let seed_type = *self.get_tile(seed).unwrap().get_type();
+            self.flood_fill(seed, |t| t.get_type() == &seed_type, neighborhood)
+        })
+    }
+
+    fn label_regions<F>(&self, mut fill: F) -> Components
+    where
+        F: FnMut(Loc) -> Region,
+    {
+        let mut labels = vec![Label::Unlabeled; self.len()];
+        let mut regions = vec![];
+        for (idx, tile) in self.tiles().iter().enumerate() {
+            if labels[idx] != Label::Unlabeled {
+                continue;
+            }
+            let region = fill(*tile.loc());
+            if region.is_empty() {
+                continue;
+            }
+            for loc in region.locs() {
+                labels[self.index_of(*loc).unwrap()] = Label::Region(regions.len());
+            }
+            regions.push(region);
+        }
+        let rows = labels
+            .chunks(self.count_columns().max(1))
+            .map(<[Label]>::to_vec)
+            .collect();
+        Components {
+            regions,
+            label_map: Collection::from_rows(rows).unwrap(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_collection;
+    use test_case::test_case;
+
+    #[derive(Debug, PartialEq, Copy, Clone)]
+    struct Plant(char);
+    impl TryFrom<char> for Plant {
+        type Error = crate::BadTileTypeError;
+
+        fn try_from(value: char) -> Result<Self, Self::Error> {
+            Ok(Self(value))
+        }
+    }
+    impl ParseableCharacters for Plant {
+        fn valid_chars() -> Vec<char> {
+            ('A'..='E').collect()
+        }
+    }
+
+    const GARDEN: &str = "\
+AAAA
+BBCD
+BBCC
+EEEC
+";
+
+    fn garden() -> Collection<Plant> {
+        parse_collection(GARDEN).unwrap().1
+    }
+
+    #[test_case(Loc::new(0, 0), 4, 10, 4; "A")]
+    #[test_case(Loc::new(0, 1), 4, 8, 4; "B")]
+    #[test_case(Loc::new(2, 1), 4, 10, 8; "C")]
+    #[test_case(Loc::new(3, 1), 1, 4, 4; "D")]
+    #[test_case(Loc::new(0, 3), 3, 8, 4; "E")]
+    fn test_region_measurements(loc: Loc, area: usize, perimeter: usize, sides: usize) {
+        let components = garden().components_by_type(&Neighborhood::Cardinal);
+        let region = components.region_of(loc).unwrap();
+        assert_eq!(region.area(), area);
+        assert_eq!(region.perimeter(), perimeter);
+        assert_eq!(region.sides(), sides);
+    }
+
+    #[test]
+    fn test_components_by_type_label_map() {
+        let components = garden().components_by_type(&Neighborhood::Cardinal);
+        assert_eq!(components.len(), 5);
+        let label_map = components.label_map();
+        let rendered: Vec<String> = (0..4).map(|y| label_map.get_row(y).to_string()).collect();
+        assert_eq!(rendered, vec!["aaaa", "bbcd", "bbcc", "eeec"]);
+    }
+
+    #[test_case(Label::Unlabeled, "."; "unlabeled")]
+    #[test_case(Label::Region(0), "a"; "first")]
+    #[test_case(Label::Region(61), "9"; "last")]
+    #[test_case(Label::Region(62), "?"; "past the last")]
+    fn test_label_display(label: Label, exp: &str) {
+        assert_eq!(label.to_string(), exp);
+    }
+
+    #[test]
+    fn test_label_round_trip() {
+        for idx in 0..LABEL_CHARS.len() {
+            let c = Label::Region(idx).to_string().chars().next().unwrap();
+            assert_eq!(Label::try_from(c), Ok(Label::Region(idx)));
+        }
+        assert!(Label::try_from('?').is_err());
+    }
+
+    #[test]
+    fn test_components_matching_predicate() {
+        let components = garden().components(&Neighborhood::Cardinal, |t| {
+            t.get_type() == &Plant('C') || t.get_type() == &Plant('D')
+        });
+        assert_eq!(components.len(), 1);
+        let label_map = components.label_map();
+        let rendered: Vec<String> = (0..4).map(|y| label_map.get_row(y).to_string()).collect();
+        assert_eq!(rendered, vec!["....", "..aa", "..aa", "...a"]);
+        assert_eq!(
+            components.regions()[0].bounding_box(),
+            Some(Rect::new(Loc::new(2, 1), Loc::new(3, 3)))
+        );
+    }
+
+    #[test_case(Neighborhood::Cardinal, 1)]
+    #[test_case(Neighborhood::All, 3)]
+    fn test_flood_fill(neighborhood: Neighborhood, exp: usize) {
+        let diagonal: Collection<Plant> = parse_collection("ABB\nBAB\nBBA\n").unwrap().1;
+        let region = diagonal.flood_fill(
+            Loc::new(0, 0),
+            |t| t.get_type() == &Plant('A'),
+            &neighborhood,
+        );
+        assert_eq!(region.area(), exp);
+    }
+
+    #[test]
+    fn test_flood_fill_from_non_matching_start() {
+        let region = garden().flood_fill(
+            Loc::new(0, 0),
+            |t| t.get_type() == &Plant('B'),
+            &Neighborhood::All,
+        );
+        assert!(region.is_empty());
+        assert_eq!(region.bounding_box(), None);
+    }
+}
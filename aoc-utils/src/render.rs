@@ -0,0 +1,114 @@
+use crate::{Collection, Loc, ParseableCharacters};
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// Writes the grid back out in puzzle format, one line per row with a trailing newline,
+/// so the output parses straight back through `parse_collection`.
+impl<T> Display for Collection<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.tiles.chunks(self.width.max(1)) {
+            for tile in row {
+                write!(f, "{tile}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Markers painted over a borrowed grid at render time, leaving the grid untouched.
+/// Later marks on the same location win.
+#[derive(Debug, Clone)]
+pub struct Overlay<'a, T> {
+    base: &'a Collection<T>,
+    marks: HashMap<Loc, char>,
+}
+
+impl<T> Overlay<'_, T> {
+    pub fn mark(mut self, loc: Loc, marker: char) -> Self {
+        self.marks.insert(loc, marker);
+        self
+    }
+    pub fn mark_all(mut self, locs: impl IntoIterator<Item = Loc>, marker: char) -> Self {
+        self.marks.extend(locs.into_iter().map(|loc| (loc, marker)));
+        self
+    }
+}
+
+impl<T> Display for Overlay<'_, T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.base.tiles.chunks(self.base.width.max(1)) {
+            for tile in row {
+                match self.marks.get(tile.loc()) {
+                    Some(marker) => write!(f, "{marker}")?,
+                    None => write!(f, "{tile}")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> Collection<T>
+where
+    T: ParseableCharacters + Copy + TryFrom<char> + PartialEq,
+{
+    pub fn overlay(&self) -> Overlay<'_, T> {
+        Overlay {
+            base: self,
+            marks: HashMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_collection;
+    use crate::tests::LavaTile;
+
+    const PATTERN: &str = "\
+#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.
+";
+
+    #[test]
+    fn test_display_round_trip() {
+        let collection: Collection<LavaTile> = parse_collection(PATTERN).unwrap().1;
+        let rendered = collection.to_string();
+        assert_eq!(rendered, PATTERN);
+        let reparsed: Collection<LavaTile> = parse_collection(&rendered).unwrap().1;
+        assert_eq!(reparsed, collection);
+    }
+
+    #[test]
+    fn test_overlay() {
+        let collection: Collection<LavaTile> = parse_collection("...\n.#.\n...\n").unwrap().1;
+        let path = [
+            Loc::new(0, 0),
+            Loc::new(1, 0),
+            Loc::new(2, 0),
+            Loc::new(2, 1),
+        ];
+        let rendered = collection
+            .overlay()
+            .mark_all(path, 'o')
+            .mark(Loc::new(2, 2), 'E')
+            .mark(Loc::new(9, 9), 'X')
+            .to_string();
+        assert_eq!(rendered, "ooo\n.#o\n..E\n");
+        assert_eq!(collection.to_string(), "...\n.#.\n...\n");
+    }
+}
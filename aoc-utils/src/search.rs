@@ -0,0 +1,544 @@
+use crate::{Collection, Loc, Neighborhood, ParseableCharacters, Tile};
+use num::Zero;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Everything a search learned: the cost to each reached node and every
+/// predecessor that lies on a shortest path to it.
+#[derive(Debug, Clone)]
+pub struct SearchResult<N, C> {
+    distances: HashMap<N, C>,
+    parents: HashMap<N, Vec<N>>,
+    goal: Option<N>,
+}
+
+impl<N, C> SearchResult<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Ord + Copy,
+{
+    pub fn distances(&self) -> &HashMap<N, C> {
+        &self.distances
+    }
+    pub fn distance(&self, node: &N) -> Option<C> {
+        self.distances.get(node).copied()
+    }
+    /// The first goal reached by a goal-directed search.
+    pub fn goal(&self) -> Option<&N> {
+        self.goal.as_ref()
+    }
+    /// The closest of several targets, for searches that explored the whole graph.
+    pub fn nearest<'a>(&self, targets: impl IntoIterator<Item = &'a N>) -> Option<(&'a N, C)>
+    where
+        N: 'a,
+    {
+        targets
+            .into_iter()
+            .filter_map(|t| Some((t, self.distance(t)?)))
+            .min_by_key(|(_, cost)| *cost)
+    }
+    /// One shortest path from a start to `target`, both ends included.
+    pub fn path_to(&self, target: &N) -> Option<Vec<N>> {
+        self.distances.get(target)?;
+        let mut path = vec![target.clone()];
+        while let Some(parent) = self
+            .parents
+            .get(path.last().unwrap())
+            .and_then(|p| p.first())
+        {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+    pub fn path_to_goal(&self) -> Option<Vec<N>> {
+        self.path_to(self.goal.as_ref()?)
+    }
+    /// Every distinct shortest path from a start to `target`.
+    pub fn all_paths_to(&self, target: &N) -> Vec<Vec<N>> {
+        if !self.distances.contains_key(target) {
+            return vec![];
+        }
+        let mut paths = vec![];
+        let mut stack = vec![vec![target.clone()]];
+        while let Some(partial) = stack.pop() {
+            match self.parents.get(partial.last().unwrap()) {
+                Some(parents) if !parents.is_empty() => {
+                    for parent in parents {
+                        let mut next = partial.clone();
+                        next.push(parent.clone());
+                        stack.push(next);
+                    }
+                }
+                _ => {
+                    let mut path = partial;
+                    path.reverse();
+                    paths.push(path);
+                }
+            }
+        }
+        paths
+    }
+}
+
+/// Breadth-first search from every start at once, exploring the whole graph.
+pub fn bfs<N, S, I>(starts: impl IntoIterator<Item = N>, successors: S) -> SearchResult<N, usize>
+where
+    N: Eq + Hash + Clone,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    bfs_to_goal(starts, successors, |_| false)
+}
+
+/// Breadth-first search that stops as soon as any node satisfying `is_goal` is reached.
+pub fn bfs_to_goal<N, S, I, G>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: S,
+    mut is_goal: G,
+) -> SearchResult<N, usize>
+where
+    N: Eq + Hash + Clone,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+    G: FnMut(&N) -> bool,
+{
+    let mut distances: HashMap<N, usize> = HashMap::new();
+    let mut parents: HashMap<N, Vec<N>> = HashMap::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if distances.insert(start.clone(), 0).is_none() {
+            queue.push_back(start);
+        }
+    }
+    let mut goal = None;
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            goal = Some(node);
+            break;
+        }
+        let next_distance = distances[&node] + 1;
+        for next in successors(&node) {
+            match distances.get(&next) {
+                None => {
+                    distances.insert(next.clone(), next_distance);
+                    parents.insert(next.clone(), vec![node.clone()]);
+                    queue.push_back(next);
+                }
+                Some(d) if *d == next_distance => {
+                    let known_parents = parents.entry(next).or_default();
+                    if !known_parents.contains(&node) {
+                        known_parents.push(node.clone());
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+    }
+    SearchResult {
+        distances,
+        parents,
+        goal,
+    }
+}
+
+/// Dijkstra from every start at once over non-negative edge costs, exploring the whole graph.
+pub fn dijkstra<N, C, S, I>(
+    starts: impl IntoIterator<Item = N>,
+    successors: S,
+) -> SearchResult<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Ord + Copy + Zero + Add<Output = C>,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(starts, successors, |_| C::zero(), |_| false)
+}
+
+/// Dijkstra that stops once the cheapest node satisfying `is_goal` is settled.
+pub fn dijkstra_to_goal<N, C, S, I, G>(
+    starts: impl IntoIterator<Item = N>,
+    successors: S,
+    is_goal: G,
+) -> SearchResult<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Ord + Copy + Zero + Add<Output = C>,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    G: FnMut(&N) -> bool,
+{
+    astar(starts, successors, |_| C::zero(), is_goal)
+}
+
+/// A* search toward any node satisfying `is_goal`. The heuristic must never
+/// overestimate the remaining cost, or the returned paths may not be shortest.
+/// It need not be consistent: a settled node is reopened when a cheaper path
+/// to it turns up later. Zero-cost edges are allowed, but where they form a
+/// loop only one way around it is recorded, so that parents never cycle.
+pub fn astar<N, C, S, I, H, G>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: S,
+    mut heuristic: H,
+    mut is_goal: G,
+) -> SearchResult<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Ord + Copy + Zero + Add<Output = C>,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    H: FnMut(&N) -> C,
+    G: FnMut(&N) -> bool,
+{
+    let mut distances: HashMap<N, C> = HashMap::new();
+    let mut parents: HashMap<N, Vec<N>> = HashMap::new();
+    let mut settled: HashSet<N> = HashSet::new();
+    // The heap orders indexes into `pending` so nodes don't need to be `Ord`.
+    let mut pending: Vec<N> = vec![];
+    let mut heap = BinaryHeap::new();
+    for start in starts {
+        if distances.insert(start.clone(), C::zero()).is_none() {
+            heap.push(Reverse((heuristic(&start), pending.len())));
+            pending.push(start);
+        }
+    }
+    let mut goal: Option<(N, C)> = None;
+    while let Some(Reverse((estimate, idx))) = heap.pop() {
+        // Keep settling ties after the goal so every equal-cost parent is recorded.
+        if goal.as_ref().is_some_and(|(_, best)| estimate > *best) {
+            break;
+        }
+        let node = pending[idx].clone();
+        if !settled.insert(node.clone()) {
+            continue;
+        }
+        let cost = distances[&node];
+        if is_goal(&node) {
+            if goal.is_none() {
+                goal = Some((node, cost));
+            }
+            continue;
+        }
+        for (next, step) in successors(&node) {
+            let next_cost = cost + step;
+            match distances.get(&next) {
+                Some(known) if *known < next_cost => {}
+                // Even a settled node can gain an equal-cost parent over a
+                // zero-cost edge, unless that parent already descends from it.
+                Some(known) if *known == next_cost => {
+                    if !descends_from(&parents, &node, &next) {
+                        let known_parents = parents.entry(next.clone()).or_default();
+                        if !known_parents.contains(&node) {
+                            known_parents.push(node.clone());
+                        }
+                    }
+                }
+                _ => {
+                    settled.remove(&next);
+                    distances.insert(next.clone(), next_cost);
+                    parents.insert(next.clone(), vec![node.clone()]);
+                    heap.push(Reverse((next_cost + heuristic(&next), pending.len())));
+                    pending.push(next);
+                }
+            }
+        }
+    }
+    SearchResult {
+        distances,
+        parents,
+        goal: goal.map(|(node, _)| node),
+    }
+}
+
+/// Whether `ancestor` is `node` itself or reachable by following its parents.
+fn descends_from<N: Eq + Hash>(parents: &HashMap<N, Vec<N>>, node: &N, ancestor: &N) -> bool {
+    let mut seen: HashSet<&N> = HashSet::new();
+    let mut stack = vec![node];
+    while let Some(current) = stack.pop() {
+        if current == ancestor {
+            return true;
+        }
+        if seen.insert(current) {
+            stack.extend(parents.get(current).into_iter().flatten());
+        }
+    }
+    false
+}
+
+impl<T> Collection<T>
+where
+    T: ParseableCharacters + Copy + TryFrom<char> + PartialEq,
+{
+    /// Breadth-first search across the grid, stepping from one tile to a
+    /// neighbor whenever `passable(from, to)` allows it.
+    pub fn bfs<P>(
+        &self,
+        starts: impl IntoIterator<Item = Loc>,
+        neighborhood: &Neighborhood,
+        passable: P,
+    ) -> SearchResult<Loc, usize>
+    where
+        P: Fn(&Tile<T>, &Tile<T>) -> bool,
+    {
+        bfs(starts, |loc| {
+            self.weighted_neighbors(*loc, neighborhood, |from, to| {
+                passable(from, to).then_some(())
+            })
+            .into_iter()
+            .map(|(next, ())| next)
+        })
+    }
+
+    /// Dijkstra across the grid, where `cost(from, to)` is `None` for impassable steps.
+    pub fn dijkstra<C, F>(
+        &self,
+        starts: impl IntoIterator<Item = Loc>,
+        neighborhood: &Neighborhood,
+        cost: F,
+    ) -> SearchResult<Loc, C>
+    where
+        C: Ord + Copy + Zero + Add<Output = C>,
+        F: Fn(&Tile<T>, &Tile<T>) -> Option<C>,
+    {
+        dijkstra(starts, |loc| {
+            self.weighted_neighbors(*loc, neighborhood, &cost)
+        })
+    }
+
+    /// A* across the grid toward the nearest of `targets`.
+    pub fn astar<C, F, H>(
+        &self,
+        starts: impl IntoIterator<Item = Loc>,
+        targets: &[Loc],
+        neighborhood: &Neighborhood,
+        cost: F,
+        heuristic: H,
+    ) -> SearchResult<Loc, C>
+    where
+        C: Ord + Copy + Zero + Add<Output = C>,
+        F: Fn(&Tile<T>, &Tile<T>) -> Option<C>,
+        H: Fn(&Loc) -> C,
+    {
+        astar(
+            starts,
+            |loc| self.weighted_neighbors(*loc, neighborhood, &cost),
+            heuristic,
+            |loc| targets.contains(loc),
+        )
+    }
+
+    fn weighted_neighbors<C, F>(
+        &self,
+        loc: Loc,
+        neighborhood: &Neighborhood,
+        cost: F,
+    ) -> Vec<(Loc, C)>
+    where
+        F: Fn(&Tile<T>, &Tile<T>) -> Option<C>,
+    {
+        let Some(from) = self.get_tile(loc) else {
+            return vec![];
+        };
+        self.neighbors(loc, neighborhood)
+            .into_iter()
+            .filter_map(|(_, to)| Some((*to.loc(), cost(from, to)?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_collection;
+    use test_case::test_case;
+
+    #[derive(Debug, PartialEq, Copy, Clone)]
+    enum MazeTile {
+        Open,
+        Wall,
+        Mud,
+    }
+    impl TryFrom<char> for MazeTile {
+        type Error = crate::BadTileTypeError;
+
+        fn try_from(value: char) -> Result<Self, Self::Error> {
+            match value {
+                '.' => Ok(Self::Open),
+                '#' => Ok(Self::Wall),
+                '~' => Ok(Self::Mud),
+                _ => Err(crate::BadTileTypeError(value)),
+            }
+        }
+    }
+    impl ParseableCharacters for MazeTile {
+        fn valid_chars() -> Vec<char> {
+            vec!['.', '#', '~']
+        }
+    }
+
+    const MAZE: &str = "\
+.....
+.###.
+.#~..
+.#.#.
+...#.
+";
+
+    fn maze() -> Collection<MazeTile> {
+        parse_collection(MAZE).unwrap().1
+    }
+
+    fn step_cost(_: &Tile<MazeTile>, to: &Tile<MazeTile>) -> Option<usize> {
+        match to.get_type() {
+            MazeTile::Open => Some(1),
+            MazeTile::Mud => Some(5),
+            MazeTile::Wall => None,
+        }
+    }
+
+    #[test_case(Loc::new(4, 4), Some(8))]
+    #[test_case(Loc::new(2, 2), Some(8); "mud counts as one step")]
+    #[test_case(Loc::new(2, 1), None; "walls are never reached")]
+    fn test_grid_bfs_distances(target: Loc, exp: Option<usize>) {
+        let actual = maze().bfs([Loc::new(0, 0)], &Neighborhood::Cardinal, |_, to| {
+            to.get_type() != &MazeTile::Wall
+        });
+        assert_eq!(actual.distance(&target), exp);
+    }
+
+    #[test]
+    fn test_grid_bfs_path_reconstruction() {
+        let actual = maze().bfs([Loc::new(0, 0)], &Neighborhood::Cardinal, |_, to| {
+            to.get_type() != &MazeTile::Wall
+        });
+        let path = actual.path_to(&Loc::new(2, 4)).unwrap();
+        assert_eq!(path.first(), Some(&Loc::new(0, 0)));
+        assert_eq!(path.last(), Some(&Loc::new(2, 4)));
+        assert_eq!(path.len(), 7);
+        assert_eq!(actual.all_paths_to(&Loc::new(4, 4)).len(), 1);
+        assert_eq!(actual.all_paths_to(&Loc::new(2, 2)).len(), 2);
+    }
+
+    #[test]
+    fn test_grid_dijkstra_avoids_mud() {
+        let actual = maze().dijkstra([Loc::new(0, 0)], &Neighborhood::Cardinal, step_cost);
+        assert_eq!(actual.distance(&Loc::new(3, 2)), Some(7));
+        assert_eq!(actual.distance(&Loc::new(2, 2)), Some(12));
+        assert!(!actual
+            .path_to(&Loc::new(3, 2))
+            .unwrap()
+            .contains(&Loc::new(2, 2)));
+    }
+
+    #[test]
+    fn test_grid_multi_source_and_target() {
+        let actual = maze().bfs(
+            [Loc::new(0, 0), Loc::new(4, 4)],
+            &Neighborhood::Cardinal,
+            |_, to| to.get_type() != &MazeTile::Wall,
+        );
+        assert_eq!(actual.distance(&Loc::new(4, 0)), Some(4));
+        let targets = [Loc::new(2, 4), Loc::new(3, 2)];
+        assert_eq!(actual.nearest(&targets), Some((&Loc::new(3, 2), 3)));
+    }
+
+    #[test]
+    fn test_grid_astar() {
+        let target = Loc::new(2, 4);
+        let actual = maze().astar(
+            [Loc::new(0, 0)],
+            &[target],
+            &Neighborhood::Cardinal,
+            step_cost,
+            |loc| loc.get_x().abs_diff(target.get_x()) + loc.get_y().abs_diff(target.get_y()),
+        );
+        assert_eq!(actual.goal(), Some(&target));
+        assert_eq!(actual.distance(&target), Some(6));
+        assert_eq!(actual.path_to_goal().unwrap().len(), 7);
+    }
+
+    #[test]
+    fn test_implicit_graph_bfs_to_goal() {
+        let actual = bfs_to_goal([1_u32], |n| [n + 1, n * 2], |n| *n == 10);
+        assert_eq!(actual.goal(), Some(&10));
+        assert_eq!(actual.distance(&10), Some(4));
+        assert_eq!(actual.path_to_goal(), Some(vec![1, 2, 4, 5, 10]));
+    }
+
+    #[test]
+    fn test_implicit_graph_dijkstra_all_paths() {
+        let edges: HashMap<char, Vec<(char, u32)>> = HashMap::from([
+            ('a', vec![('b', 1), ('c', 2)]),
+            ('b', vec![('d', 2)]),
+            ('c', vec![('d', 1)]),
+            ('d', vec![('e', 5)]),
+        ]);
+        let actual = dijkstra(['a'], |n| edges.get(n).cloned().unwrap_or_default());
+        assert_eq!(actual.distance(&'e'), Some(8));
+        let mut paths = actual.all_paths_to(&'e');
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![vec!['a', 'b', 'd', 'e'], vec!['a', 'c', 'd', 'e']]
+        );
+    }
+
+    #[test]
+    fn test_implicit_graph_astar_reopens_settled_nodes() {
+        let edges: HashMap<char, Vec<(char, u32)>> = HashMap::from([
+            ('s', vec![('a', 1), ('b', 2)]),
+            ('a', vec![('c', 3)]),
+            ('b', vec![('c', 1)]),
+            ('c', vec![('g', 3)]),
+        ]);
+        // Admissible but inconsistent, so `c` is first settled through `a`.
+        let actual = astar(
+            ['s'],
+            |n| edges.get(n).cloned().unwrap_or_default(),
+            |n| if *n == 'b' { 4 } else { 0 },
+            |n| *n == 'g',
+        );
+        assert_eq!(actual.distance(&'g'), Some(6));
+        assert_eq!(actual.path_to_goal(), Some(vec!['s', 'b', 'c', 'g']));
+    }
+
+    #[test_case(vec![('b', 0)], vec![vec!['s', 'a', 'b', 't'], vec!['s', 'b', 't']]; "zero-cost edge")]
+    #[test_case(vec![('b', 0), ('s', 0)], vec![vec!['s', 'a', 'b', 't'], vec!['s', 'b', 't']]; "zero-cost loop back to the start")]
+    fn test_implicit_graph_dijkstra_zero_cost_edges(from_a: Vec<(char, u32)>, exp: Vec<Vec<char>>) {
+        // `b` is settled before `a` offers it an equal-cost parent.
+        let edges: HashMap<char, Vec<(char, u32)>> = HashMap::from([
+            ('s', vec![('b', 1), ('a', 1)]),
+            ('a', from_a),
+            ('b', vec![('t', 1)]),
+        ]);
+        let actual = dijkstra(['s'], |n| edges.get(n).cloned().unwrap_or_default());
+        assert_eq!(actual.distance(&'t'), Some(2));
+        let mut paths = actual.all_paths_to(&'t');
+        paths.sort();
+        assert_eq!(paths, exp);
+    }
+
+    #[test]
+    fn test_implicit_graph_zero_cost_loop_terminates() {
+        let edges: HashMap<char, Vec<(char, u32)>> = HashMap::from([
+            ('s', vec![('a', 1), ('b', 1)]),
+            ('a', vec![('b', 0)]),
+            ('b', vec![('a', 0), ('t', 1)]),
+        ]);
+        let actual = dijkstra(['s'], |n| edges.get(n).cloned().unwrap_or_default());
+        let paths = actual.all_paths_to(&'t');
+        assert!(paths.contains(&vec!['s', 'b', 't']));
+        assert!(paths.iter().all(|p| p.len() <= 4));
+    }
+
+    #[test]
+    fn test_implicit_graph_dijkstra_to_goal() {
+        let actual = dijkstra_to_goal([0_i64], |n| [(n + 3, 1_u32), (n - 2, 1)], |n| *n == 7);
+        assert_eq!(actual.goal(), Some(&7));
+        assert_eq!(actual.distance(&7), Some(4));
+        assert!(actual.all_paths_to(&7).len() > 1);
+    }
+}
@@ -0,0 +1,192 @@
+use crate::{Collection, Loc, ParseableCharacters, Rect};
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// An unbounded grid that only stores the tiles that have been set, so it can
+/// grow in any direction, including into negative coordinates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseGrid<T> {
+    tiles: HashMap<Loc, T>,
+    bounds: Option<Rect>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self {
+            tiles: HashMap::new(),
+            bounds: None,
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+    /// The smallest rectangle covering every stored tile.
+    pub fn bounds(&self) -> Option<Rect> {
+        self.bounds
+    }
+    pub fn contains(&self, loc: &Loc) -> bool {
+        self.tiles.contains_key(loc)
+    }
+    pub fn get(&self, loc: &Loc) -> Option<&T> {
+        self.tiles.get(loc)
+    }
+    pub fn get_mut(&mut self, loc: &Loc) -> Option<&mut T> {
+        self.tiles.get_mut(loc)
+    }
+    /// Stores a tile, returning whatever was there before.
+    pub fn set(&mut self, loc: Loc, tile: T) -> Option<T> {
+        self.bounds = Rect::bounding(
+            self.bounds
+                .into_iter()
+                .flat_map(|b| [b.min(), b.max()])
+                .chain([loc]),
+        );
+        self.tiles.insert(loc, tile)
+    }
+    pub fn remove(&mut self, loc: &Loc) -> Option<T> {
+        let removed = self.tiles.remove(loc)?;
+        let on_edge = self.bounds.is_some_and(|b| {
+            loc.x == b.min().x || loc.x == b.max().x || loc.y == b.min().y || loc.y == b.max().y
+        });
+        if on_edge {
+            self.bounds = Rect::bounding(self.tiles.keys().copied());
+        }
+        Some(removed)
+    }
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&Loc, &T) -> bool,
+    {
+        self.tiles.retain(|loc, tile| keep(loc, tile));
+        self.bounds = Rect::bounding(self.tiles.keys().copied());
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&Loc, &T)> {
+        self.tiles.iter()
+    }
+
+    /// Draws the bounding box of the grid, using `fill` wherever no tile is stored.
+    pub fn render(&self, fill: char) -> String
+    where
+        T: Display,
+    {
+        let Some(bounds) = self.bounds else {
+            return String::new();
+        };
+        let mut out = String::new();
+        for y in bounds.min().y..=bounds.max().y {
+            for x in bounds.min().x..=bounds.max().x {
+                match self.tiles.get(&Loc::new(x, y)) {
+                    Some(tile) => out.push_str(&tile.to_string()),
+                    None => out.push(fill),
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl<T> SparseGrid<T>
+where
+    T: ParseableCharacters + Copy + TryFrom<char> + PartialEq,
+{
+    /// A dense copy of the bounding box, shifted so its top-left corner is `0,0`
+    /// and with `fill` wherever no tile is stored.
+    pub fn to_collection(&self, fill: T) -> Collection<T> {
+        let Some(bounds) = self.bounds else {
+            return Collection::from_rows(vec![]).unwrap();
+        };
+        let rows = (bounds.min().y..=bounds.max().y)
+            .map(|y| {
+                (bounds.min().x..=bounds.max().x)
+                    .map(|x| self.tiles.get(&Loc::new(x, y)).copied().unwrap_or(fill))
+                    .collect()
+            })
+            .collect();
+        Collection::from_rows(rows).unwrap()
+    }
+}
+
+impl<T> From<&Collection<T>> for SparseGrid<T>
+where
+    T: Copy,
+{
+    fn from(value: &Collection<T>) -> Self {
+        Self {
+            tiles: value.tiles.iter().map(|t| (t.loc, t.tile_type)).collect(),
+            bounds: Rect::bounding(value.tiles.iter().map(|t| t.loc)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_collection;
+    use crate::tests::LavaTile;
+
+    #[test]
+    fn test_bounds_grow_and_shrink() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+        grid.set(Loc::new(0, 0), LavaTile::Rocks);
+        grid.set(Loc::new(-3, 2), LavaTile::Rocks);
+        grid.set(Loc::new(1, -1), LavaTile::Ash);
+        assert_eq!(
+            grid.bounds(),
+            Some(Rect::new(Loc::new(-3, -1), Loc::new(1, 2)))
+        );
+        assert_eq!(grid.remove(&Loc::new(-3, 2)), Some(LavaTile::Rocks));
+        assert_eq!(grid.remove(&Loc::new(-3, 2)), None);
+        assert_eq!(
+            grid.bounds(),
+            Some(Rect::new(Loc::new(0, -1), Loc::new(1, 0)))
+        );
+        assert_eq!(grid.len(), 2);
+    }
+
+    #[test]
+    fn test_set_replaces() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.set(Loc::new(5, 5), LavaTile::Ash), None);
+        assert_eq!(
+            grid.set(Loc::new(5, 5), LavaTile::Rocks),
+            Some(LavaTile::Ash)
+        );
+        assert_eq!(grid.get(&Loc::new(5, 5)), Some(&LavaTile::Rocks));
+    }
+
+    #[test]
+    fn test_render() {
+        let mut grid = SparseGrid::new();
+        grid.set(Loc::new(-1, -1), LavaTile::Rocks);
+        grid.set(Loc::new(1, 0), LavaTile::Rocks);
+        grid.set(Loc::new(0, 1), LavaTile::Ash);
+        assert_eq!(grid.render(' '), "#  \n  #\n . \n");
+        assert_eq!(SparseGrid::<LavaTile>::new().render(' '), "");
+    }
+
+    #[test]
+    fn test_collection_round_trip() {
+        let collection: Collection<LavaTile> = parse_collection("#..\n.#.\n").unwrap().1;
+        let mut grid = SparseGrid::from(&collection);
+        assert_eq!(grid.len(), 6);
+        assert_eq!(grid.to_collection(LavaTile::Ash), collection);
+
+        grid.retain(|_, tile| tile == &LavaTile::Rocks);
+        grid.set(Loc::new(-1, 2), LavaTile::Rocks);
+        assert_eq!(
+            grid.to_collection(LavaTile::Ash).to_string(),
+            ".#.\n..#\n#..\n"
+        );
+    }
+}
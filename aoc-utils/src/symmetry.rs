@@ -0,0 +1,179 @@
+use crate::{Collection, CollectionGroup, Loc, ParseableCharacters};
+
+/// A line the grid can be folded along. `Horizontal(n)` sits below the first
+/// `n` rows and `Vertical(n)` sits right of the first `n` columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MirrorLine {
+    Horizontal(usize),
+    Vertical(usize),
+}
+
+impl MirrorLine {
+    /// Columns left of a vertical line, or 100 times the rows above a horizontal one.
+    pub fn summarize(&self) -> usize {
+        match self {
+            MirrorLine::Horizontal(rows) => rows * 100,
+            MirrorLine::Vertical(columns) => *columns,
+        }
+    }
+}
+
+/// A mirror line along with each pair of reflected tiles that didn't match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reflection {
+    line: MirrorLine,
+    smudges: Vec<(Loc, Loc)>,
+}
+
+impl Reflection {
+    pub fn line(&self) -> MirrorLine {
+        self.line
+    }
+    pub fn smudges(&self) -> &[(Loc, Loc)] {
+        &self.smudges
+    }
+}
+
+impl<T> Collection<T>
+where
+    T: ParseableCharacters + Copy + TryFrom<char> + PartialEq,
+{
+    /// Every mirror line across which exactly `exact_smudges` pairs of tiles
+    /// differ. A perfect mirror has no smudges, so it is only found when
+    /// `exact_smudges` is 0.
+    pub fn find_reflections(&self, exact_smudges: usize) -> Vec<Reflection> {
+        let horizontal = (1..self.height).filter_map(|rows| {
+            let pairs = (0..rows.min(self.height - rows)).flat_map(|offset| {
+                (0..self.width).map(move |x| {
+                    (
+                        Loc::new(x as isize, (rows - 1 - offset) as isize),
+                        Loc::new(x as isize, (rows + offset) as isize),
+                    )
+                })
+            });
+            self.reflection_along(MirrorLine::Horizontal(rows), pairs, exact_smudges)
+        });
+        let vertical = (1..self.width).filter_map(|columns| {
+            let pairs = (0..columns.min(self.width - columns)).flat_map(|offset| {
+                (0..self.height).map(move |y| {
+                    (
+                        Loc::new((columns - 1 - offset) as isize, y as isize),
+                        Loc::new((columns + offset) as isize, y as isize),
+                    )
+                })
+            });
+            self.reflection_along(MirrorLine::Vertical(columns), pairs, exact_smudges)
+        });
+        horizontal.chain(vertical).collect()
+    }
+
+    /// The first mirror line with exactly `exact_smudges` mismatches, horizontal
+    /// lines first.
+    pub fn find_reflection(&self, exact_smudges: usize) -> Option<Reflection> {
+        self.find_reflections(exact_smudges).into_iter().next()
+    }
+
+    /// The order of the grid's rotational symmetry: how many of the four
+    /// quarter-turn rotations, counting no turn at all, leave it unchanged.
+    /// Always 4, 2 or 1.
+    pub fn rotational_symmetry(&self) -> usize {
+        if self.width == self.height && self.rotate_cw() == *self {
+            4
+        } else if self.rotate_180() == *self {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn reflection_along(
+        &self,
+        line: MirrorLine,
+        pairs: impl Iterator<Item = (Loc, Loc)>,
+        exact_smudges: usize,
+    ) -> Option<Reflection> {
+        let mut mismatches = vec![];
+        for (near, far) in pairs {
+            if self.get_tile(near) != self.get_tile(far) {
+                mismatches.push((near, far));
+                if mismatches.len() > exact_smudges {
+                    return None;
+                }
+            }
+        }
+        (mismatches.len() == exact_smudges).then_some(Reflection {
+            line,
+            smudges: mismatches,
+        })
+    }
+}
+
+/// Sums the summary of the first reflection with exactly `exact_smudges`
+/// mismatches in each collection of the group.
+pub fn summarize_reflections<T>(group: &CollectionGroup<T>, exact_smudges: usize) -> usize
+where
+    T: ParseableCharacters + Copy + TryFrom<char> + PartialEq,
+{
+    group
+        .iter()
+        .filter_map(|c| c.find_reflection(exact_smudges))
+        .map(|r| r.line().summarize())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::LavaTile;
+    use crate::{parse_collection, parse_collection_group};
+    use test_case::test_case;
+
+    fn lava_group() -> CollectionGroup<LavaTile> {
+        parse_collection_group(include_str!("./data/lava_sample.txt"))
+            .unwrap()
+            .1
+    }
+
+    #[test]
+    fn test_find_reflections_counts_smudges_exactly() {
+        let lines = |smudges| {
+            lava_group()[0]
+                .find_reflections(smudges)
+                .iter()
+                .map(Reflection::line)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(lines(0), vec![MirrorLine::Vertical(5)]);
+        assert_eq!(lines(1), vec![MirrorLine::Horizontal(3)]);
+    }
+
+    #[test_case(0, 0, MirrorLine::Vertical(5))]
+    #[test_case(1, 0, MirrorLine::Horizontal(4))]
+    #[test_case(0, 1, MirrorLine::Horizontal(3))]
+    #[test_case(1, 1, MirrorLine::Horizontal(1))]
+    fn test_find_reflection(idx: usize, smudges: usize, exp: MirrorLine) {
+        let actual = lava_group()[idx].find_reflection(smudges).unwrap();
+        assert_eq!(actual.line(), exp);
+        assert_eq!(actual.smudges().len(), smudges);
+    }
+
+    #[test]
+    fn test_smudge_locations() {
+        let actual = lava_group()[0].find_reflection(1).unwrap();
+        assert_eq!(actual.smudges(), &[(Loc::new(0, 0), Loc::new(0, 5))]);
+    }
+
+    #[test_case(0, 405)]
+    #[test_case(1, 400)]
+    fn test_summarize_reflections(smudges: usize, exp: usize) {
+        assert_eq!(summarize_reflections(&lava_group(), smudges), exp);
+    }
+
+    #[test_case("#..\n...\n..#\n", 2; "half turn")]
+    #[test_case(".#.\n#.#\n.#.\n", 4; "quarter turn")]
+    #[test_case("##.\n...\n...\n", 1; "none")]
+    fn test_rotational_symmetry(inp: &str, exp: usize) {
+        let collection: Collection<LavaTile> = parse_collection(inp).unwrap().1;
+        assert_eq!(collection.rotational_symmetry(), exp);
+    }
+}
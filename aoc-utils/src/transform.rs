@@ -0,0 +1,202 @@
+use crate::{Collection, ParseableCharacters, Rect};
+
+impl<T> Collection<T>
+where
+    T: ParseableCharacters + Copy + TryFrom<char> + PartialEq,
+{
+    fn type_at(&self, x: usize, y: usize) -> T {
+        self.tiles[y * self.width + x].tile_type
+    }
+
+    /// Builds a new collection of the given size, asking `tile_at` for the type at each `(x, y)`.
+    fn build<F>(width: usize, height: usize, tile_at: F) -> Self
+    where
+        F: Fn(usize, usize) -> T,
+    {
+        let rows = (0..height)
+            .map(|y| (0..width).map(|x| tile_at(x, y)).collect())
+            .collect();
+        Self::from_rows(rows).unwrap()
+    }
+
+    pub fn rotate_cw(&self) -> Self {
+        Self::build(self.height, self.width, |x, y| {
+            self.type_at(y, self.height - 1 - x)
+        })
+    }
+
+    pub fn rotate_ccw(&self) -> Self {
+        Self::build(self.height, self.width, |x, y| {
+            self.type_at(self.width - 1 - y, x)
+        })
+    }
+
+    pub fn rotate_180(&self) -> Self {
+        Self::build(self.width, self.height, |x, y| {
+            self.type_at(self.width - 1 - x, self.height - 1 - y)
+        })
+    }
+
+    /// Mirrors the grid across its main diagonal, so rows become columns.
+    pub fn transpose(&self) -> Self {
+        Self::build(self.height, self.width, |x, y| self.type_at(y, x))
+    }
+
+    /// Mirrors the grid left to right.
+    pub fn flip_horizontal(&self) -> Self {
+        Self::build(self.width, self.height, |x, y| {
+            self.type_at(self.width - 1 - x, y)
+        })
+    }
+
+    /// Mirrors the grid top to bottom.
+    pub fn flip_vertical(&self) -> Self {
+        Self::build(self.width, self.height, |x, y| {
+            self.type_at(x, self.height - 1 - y)
+        })
+    }
+
+    /// The part of the grid inside `rect`, re-anchored at `0,0`. Anything outside
+    /// the grid is dropped, so a rect that misses it entirely gives an empty collection.
+    pub fn crop(&self, rect: Rect) -> Self {
+        let min_x = rect.min().x.max(0);
+        let min_y = rect.min().y.max(0);
+        let max_x = rect.max().x.min(self.width as isize - 1);
+        let max_y = rect.max().y.min(self.height as isize - 1);
+        if min_x > max_x || min_y > max_y {
+            return Self::empty();
+        }
+        let (min_x, min_y) = (min_x.unsigned_abs(), min_y.unsigned_abs());
+        Self::build(
+            max_x.unsigned_abs() - min_x + 1,
+            max_y.unsigned_abs() - min_y + 1,
+            |x, y| self.type_at(x + min_x, y + min_y),
+        )
+    }
+
+    /// Surrounds the grid with a border of `fill` tiles, `n` deep on every side.
+    pub fn pad(&self, n: usize, fill: T) -> Self {
+        Self::build(self.width + 2 * n, self.height + 2 * n, |x, y| {
+            let inside = (n..n + self.width).contains(&x) && (n..n + self.height).contains(&y);
+            if inside {
+                self.type_at(x - n, y - n)
+            } else {
+                fill
+            }
+        })
+    }
+
+    /// All eight rotations and reflections of the grid: each quarter turn
+    /// clockwise from the original, followed by its horizontal mirror image.
+    pub fn orientations(&self) -> Vec<Self> {
+        let mut orientations = Vec::with_capacity(8);
+        let mut current = self.clone();
+        for _ in 0..4 {
+            let mirrored = current.flip_horizontal();
+            let next = current.rotate_cw();
+            orientations.push(current);
+            orientations.push(mirrored);
+            current = next;
+        }
+        orientations
+    }
+
+    fn empty() -> Self {
+        Self::from_rows(vec![]).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_collection, Loc};
+    use test_case::test_case;
+
+    #[derive(Debug, PartialEq, Copy, Clone)]
+    struct Glyph(char);
+    impl std::fmt::Display for Glyph {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+    impl TryFrom<char> for Glyph {
+        type Error = crate::BadTileTypeError;
+
+        fn try_from(value: char) -> Result<Self, Self::Error> {
+            Ok(Self(value))
+        }
+    }
+    impl ParseableCharacters for Glyph {
+        fn valid_chars() -> Vec<char> {
+            let mut chars: Vec<char> = ('a'..='z').collect();
+            chars.push('.');
+            chars
+        }
+    }
+
+    fn grid() -> Collection<Glyph> {
+        parse_collection("abc\ndef\n").unwrap().1
+    }
+
+    fn render(collection: &Collection<Glyph>) -> Vec<String> {
+        (0..collection.count_rows() as isize)
+            .map(|y| collection.get_row(y).to_string())
+            .collect()
+    }
+
+    #[test_case(Collection::rotate_cw, vec!["da", "eb", "fc"]; "rotate_cw")]
+    #[test_case(Collection::rotate_ccw, vec!["cf", "be", "ad"]; "rotate_ccw")]
+    #[test_case(Collection::rotate_180, vec!["fed", "cba"]; "rotate_180")]
+    #[test_case(Collection::transpose, vec!["ad", "be", "cf"]; "transpose")]
+    #[test_case(Collection::flip_horizontal, vec!["cba", "fed"]; "flip_horizontal")]
+    #[test_case(Collection::flip_vertical, vec!["def", "abc"]; "flip_vertical")]
+    fn test_transform(transform: fn(&Collection<Glyph>) -> Collection<Glyph>, exp: Vec<&str>) {
+        let actual = transform(&grid());
+        assert_eq!(render(&actual), exp);
+        assert_eq!(actual.count_rows(), exp.len());
+        let width = actual.count_columns();
+        for (idx, tile) in actual.tiles().iter().enumerate() {
+            let exp_loc = Loc::new((idx % width) as isize, (idx / width) as isize);
+            assert_eq!(tile.loc(), &exp_loc);
+        }
+    }
+
+    #[test]
+    fn test_rotations_compose() {
+        let grid = grid();
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+        assert_eq!(grid.rotate_cw().rotate_cw(), grid.rotate_180());
+        assert_eq!(grid.transpose().transpose(), grid);
+    }
+
+    #[test_case(Rect::new(Loc::new(1, 0), Loc::new(2, 1)), vec!["bc", "ef"]; "inside")]
+    #[test_case(Rect::new(Loc::new(-3, 1), Loc::new(1, 9)), vec!["de"]; "clipped")]
+    #[test_case(Rect::new(Loc::new(5, 5), Loc::new(6, 6)), vec![]; "outside")]
+    fn test_crop(rect: Rect, exp: Vec<&str>) {
+        let actual = grid().crop(rect);
+        assert_eq!(render(&actual), exp);
+        assert_eq!(actual.get_tile(Loc::new(0, 0)).is_some(), !exp.is_empty());
+    }
+
+    #[test]
+    fn test_pad() {
+        let actual = grid().pad(1, Glyph('.'));
+        assert_eq!(render(&actual), vec![".....", ".abc.", ".def.", "....."]);
+        assert_eq!(
+            actual.get_tile(Loc::new(1, 1)).unwrap().get_type(),
+            &Glyph('a')
+        );
+    }
+
+    #[test]
+    fn test_orientations() {
+        let actual: Vec<Vec<String>> = grid().orientations().iter().map(render).collect();
+        assert_eq!(actual.len(), 8);
+        assert_eq!(actual[0], vec!["abc", "def"]);
+        assert_eq!(actual[1], vec!["cba", "fed"]);
+        assert_eq!(actual[2], vec!["da", "eb", "fc"]);
+        for (idx, orientation) in actual.iter().enumerate() {
+            assert!(!actual[idx + 1..].contains(orientation));
+        }
+    }
+}
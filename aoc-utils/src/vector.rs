@@ -0,0 +1,257 @@
+use crate::{Direction, Loc};
+use nom::{
+    character::complete::{char, i64 as parse_i64, space0},
+    combinator::map,
+    sequence::{delimited, separated_pair},
+    IResult,
+};
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+/// The displacement between two locations.
+#[derive(Debug, PartialEq, Copy, Clone, PartialOrd, Eq, Ord, Hash, Default)]
+pub struct Offset {
+    dx: isize,
+    dy: isize,
+}
+
+impl Offset {
+    pub fn new(dx: isize, dy: isize) -> Self {
+        Self { dx, dy }
+    }
+    pub fn get_dx(&self) -> isize {
+        self.dx
+    }
+    pub fn get_dy(&self) -> isize {
+        self.dy
+    }
+    /// A quarter turn clockwise, with y growing downward as it does in puzzle input.
+    pub fn rotate_clockwise(&self) -> Self {
+        Self::new(-self.dy, self.dx)
+    }
+    pub fn rotate_counterclockwise(&self) -> Self {
+        Self::new(self.dy, -self.dx)
+    }
+    pub fn manhattan_length(&self) -> usize {
+        self.dx.unsigned_abs() + self.dy.unsigned_abs()
+    }
+    pub fn chebyshev_length(&self) -> usize {
+        self.dx.unsigned_abs().max(self.dy.unsigned_abs())
+    }
+    pub fn squared_length(&self) -> usize {
+        self.dx.unsigned_abs().pow(2) + self.dy.unsigned_abs().pow(2)
+    }
+}
+
+impl From<(isize, isize)> for Offset {
+    fn from((dx, dy): (isize, isize)) -> Self {
+        Self::new(dx, dy)
+    }
+}
+
+impl Add for Offset {
+    type Output = Offset;
+
+    fn add(self, rhs: Offset) -> Self::Output {
+        Offset::new(self.dx + rhs.dx, self.dy + rhs.dy)
+    }
+}
+
+impl Sub for Offset {
+    type Output = Offset;
+
+    fn sub(self, rhs: Offset) -> Self::Output {
+        Offset::new(self.dx - rhs.dx, self.dy - rhs.dy)
+    }
+}
+
+impl Neg for Offset {
+    type Output = Offset;
+
+    fn neg(self) -> Self::Output {
+        Offset::new(-self.dx, -self.dy)
+    }
+}
+
+impl Mul<isize> for Offset {
+    type Output = Offset;
+
+    fn mul(self, rhs: isize) -> Self::Output {
+        Offset::new(self.dx * rhs, self.dy * rhs)
+    }
+}
+
+impl Add<Offset> for Loc {
+    type Output = Loc;
+
+    fn add(self, rhs: Offset) -> Self::Output {
+        Loc::new(self.x + rhs.dx, self.y + rhs.dy)
+    }
+}
+
+impl AddAssign<Offset> for Loc {
+    fn add_assign(&mut self, rhs: Offset) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<Offset> for Loc {
+    type Output = Loc;
+
+    fn sub(self, rhs: Offset) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl SubAssign<Offset> for Loc {
+    fn sub_assign(&mut self, rhs: Offset) {
+        *self = *self - rhs;
+    }
+}
+
+impl Sub for Loc {
+    type Output = Offset;
+
+    fn sub(self, rhs: Loc) -> Self::Output {
+        Offset::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl From<(isize, isize)> for Loc {
+    fn from((x, y): (isize, isize)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BadLocError(String);
+
+/// Parses `x,y` text, allowing spaces around the comma.
+impl FromStr for Loc {
+    type Err = BadLocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match parse_loc(s.trim()) {
+            Ok(("", loc)) => Ok(loc),
+            _ => Err(BadLocError(s.into())),
+        }
+    }
+}
+
+pub fn parse_loc(inp: &str) -> IResult<&str, Loc> {
+    map(
+        separated_pair(parse_i64, delimited(space0, char(','), space0), parse_i64),
+        |(x, y)| Loc::new(x as isize, y as isize),
+    )(inp)
+}
+
+impl Loc {
+    pub fn manhattan_distance(&self, other: &Loc) -> usize {
+        (*other - *self).manhattan_length()
+    }
+    pub fn chebyshev_distance(&self, other: &Loc) -> usize {
+        (*other - *self).chebyshev_length()
+    }
+    pub fn squared_distance(&self, other: &Loc) -> usize {
+        (*other - *self).squared_length()
+    }
+    pub fn rotate_clockwise_about(&self, pivot: Loc) -> Loc {
+        pivot + (*self - pivot).rotate_clockwise()
+    }
+    pub fn rotate_counterclockwise_about(&self, pivot: Loc) -> Loc {
+        pivot + (*self - pivot).rotate_counterclockwise()
+    }
+}
+
+impl Direction {
+    pub fn to_offset(&self) -> Offset {
+        match self {
+            Direction::North => Offset::new(0, -1),
+            Direction::East => Offset::new(1, 0),
+            Direction::South => Offset::new(0, 1),
+            Direction::West => Offset::new(-1, 0),
+            Direction::NorthEast => Offset::new(1, -1),
+            Direction::SouthEast => Offset::new(1, 1),
+            Direction::SouthWest => Offset::new(-1, 1),
+            Direction::NorthWest => Offset::new(-1, -1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test]
+    fn test_arithmetic() {
+        let loc = Loc::new(3, -2);
+        let offset = Offset::new(1, 4);
+        assert_eq!(loc + offset, Loc::new(4, 2));
+        assert_eq!(loc - offset, Loc::new(2, -6));
+        assert_eq!(Loc::new(4, 2) - loc, offset);
+        assert_eq!(-offset, Offset::new(-1, -4));
+        assert_eq!(offset * 3, Offset::new(3, 12));
+        assert_eq!(offset + Offset::new(-1, -4), Offset::default());
+        let mut moving = loc;
+        moving += offset;
+        moving -= offset * 2;
+        assert_eq!(moving, Loc::new(2, -6));
+    }
+
+    #[test_case(Loc::new(0, 0), Loc::new(3, -4), 7, 4, 25)]
+    #[test_case(Loc::new(-2, 5), Loc::new(-2, 5), 0, 0, 0)]
+    #[test_case(Loc::new(1, 1), Loc::new(-1, 2), 3, 2, 5)]
+    fn test_distances(a: Loc, b: Loc, manhattan: usize, chebyshev: usize, squared: usize) {
+        assert_eq!(a.manhattan_distance(&b), manhattan);
+        assert_eq!(a.chebyshev_distance(&b), chebyshev);
+        assert_eq!(a.squared_distance(&b), squared);
+        assert_eq!(b.manhattan_distance(&a), manhattan);
+    }
+
+    #[test]
+    fn test_direction_offsets_match_get_nearby() {
+        let origin = Loc::new(5, 5);
+        for direction in Direction::get_all() {
+            assert_eq!(
+                Some(origin + direction.to_offset()),
+                origin.get_nearby(direction, 1)
+            );
+            assert_eq!(
+                direction.to_offset().rotate_clockwise(),
+                direction.rotate_clockwise().to_offset()
+            );
+            assert_eq!(
+                direction.to_offset().rotate_counterclockwise(),
+                direction.rotate_counterclockwise().to_offset()
+            );
+        }
+    }
+
+    #[test]
+    fn test_rotate_about_pivot() {
+        let pivot = Loc::new(2, 2);
+        let loc = Loc::new(4, 1);
+        assert_eq!(loc.rotate_clockwise_about(pivot), Loc::new(3, 4));
+        assert_eq!(loc.rotate_counterclockwise_about(pivot), Loc::new(1, 0));
+        assert_eq!(
+            loc.rotate_clockwise_about(pivot)
+                .rotate_counterclockwise_about(pivot),
+            loc
+        );
+    }
+
+    #[test_case("7,1", Ok(Loc::new(7, 1)))]
+    #[test_case(" -3 , 12\n", Ok(Loc::new(-3, 12)); "spaces and newline")]
+    #[test_case("7;1", Err(BadLocError("7;1".into())); "bad separator")]
+    #[test_case("7,1,2", Err(BadLocError("7,1,2".into())); "trailing")]
+    fn test_parse_loc(inp: &str, exp: Result<Loc, BadLocError>) {
+        assert_eq!(inp.parse::<Loc>(), exp);
+    }
+
+    #[test]
+    fn test_from_tuple() {
+        assert_eq!(Loc::from((1, -1)), Loc::new(1, -1));
+        assert_eq!(Offset::from((1, -1)), Offset::new(1, -1));
+    }
+}
@@ -1,4 +1,9 @@
 #![allow(dead_code)]
+use aoc_utils::search::dijkstra_to_goal;
+use num::{PrimInt, Zero};
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Display};
+use std::ops::Add;
 
 #[cfg(test)]
 pub const PUZZLE_INPUT: &str = include_str!("../../data/sample_input.txt");
@@ -9,7 +14,11 @@ pub const PUZZLE_INPUT: &str = include_str!("../../data/puzzle_input.txt");
 const MIN_FACE: u8 = 0;
 const MAX_FACE: u8 = 99;
 
-#[derive(PartialEq, Debug)]
+/// Any integer a dial's faces can be numbered with.
+pub trait Face: PrimInt + Debug {}
+impl<T> Face for T where T: PrimInt + Debug {}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 pub enum Direction {
     L,
     R,
@@ -27,60 +36,217 @@ impl From<&str> for Direction {
 #[derive(PartialEq, Debug)]
 pub struct Instruction {
     direction: Direction,
-    count: u16,
+    count: u32,
+}
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}{}", self.direction, self.count)
+    }
+}
+impl From<&str> for Instruction {
+    fn from(value: &str) -> Self {
+        let (direction, count) = value.split_at(1);
+        Self {
+            direction: Direction::from(direction),
+            count: count.parse::<u32>().unwrap(),
+        }
+    }
+}
+
+/// What one instruction did to the dial.
+#[derive(PartialEq, Debug, Clone)]
+pub struct DialEvent<T> {
+    /// The face the dial points at once the instruction is done.
+    position: T,
+    /// How many clicks, including the last, left the dial on each target.
+    clicks: BTreeMap<T, usize>,
+    /// How many complete turns the instruction made.
+    wraps: usize,
+}
+impl<T: Face> DialEvent<T> {
+    pub fn position(&self) -> T {
+        self.position
+    }
+    pub fn clicks_on(&self, target: &T) -> usize {
+        self.clicks.get(target).copied().unwrap_or_default()
+    }
+    pub fn wraps(&self) -> usize {
+        self.wraps
+    }
 }
 
 #[derive(PartialEq, Debug)]
-pub struct Results {
-    exact_mins: usize,
-    passed_mins: usize,
-}
-pub struct Dial {
-    min: u8,
-    max: u8,
-    start: u8,
-}
-
-impl Dial {
-    pub fn new(min: u8, max: u8, start: u8) -> Self {
-        Self { min, max, start }
-    }
-
-    #[allow(clippy::cast_possible_wrap)]
-    pub fn run(&self, instructions: &InstructionSet) -> Results {
-        let mut exact_mins: usize = 0;
-        let mut passed_mins: usize = 0;
-        let mut current_val: isize = self.start.into();
-        for inst in &instructions.0 {
-            let full_rotations: usize = (inst.count / u16::from(self.max + 1)).into();
-            passed_mins += full_rotations;
-            let count = inst.count % u16::from(self.max+1);
-            match inst.direction {
-                Direction::L => {
-                    current_val -= isize::try_from(count).unwrap();
-                    if current_val < self.min.into() {
-                        passed_mins += 1;
-                        current_val += self.max as isize + 1;
-                    }
-                }
-                Direction::R => {
-                    current_val += isize::try_from(count).unwrap();
-                    if current_val > self.max.into() {
-                        passed_mins += 1;
-                        current_val -= self.max as isize + 1;
-                    }
-                }
+pub struct Results<T> {
+    exact: BTreeMap<T, usize>,
+    passed: BTreeMap<T, usize>,
+}
+impl<T: Face> Results<T> {
+    /// Instructions that finished with the dial on `target`.
+    pub fn exact_hits(&self, target: &T) -> usize {
+        self.exact.get(target).copied().unwrap_or_default()
+    }
+    /// Clicks, at any point during an instruction, that landed on `target`.
+    pub fn passed_hits(&self, target: &T) -> usize {
+        self.passed.get(target).copied().unwrap_or_default()
+    }
+    pub fn exact_total(&self) -> usize {
+        self.exact.values().sum()
+    }
+    pub fn passed_total(&self) -> usize {
+        self.passed.values().sum()
+    }
+}
+
+/// A dial with faces `min..=max` that counts how often it reaches its targets,
+/// which are just `min` unless set otherwise.
+pub struct Dial<T> {
+    min: T,
+    max: T,
+    start: T,
+    targets: Vec<T>,
+}
+
+impl<T: Face> Dial<T> {
+    pub fn new(min: T, max: T, start: T) -> Self {
+        assert!(min <= start && start <= max, "start must be on the dial");
+        Self {
+            min,
+            max,
+            start,
+            targets: vec![min],
+        }
+    }
+
+    pub fn with_targets(mut self, targets: impl IntoIterator<Item = T>) -> Self {
+        self.targets = targets.into_iter().collect();
+        assert!(
+            self.targets.iter().all(|t| self.min <= *t && *t <= self.max),
+            "targets must be on the dial"
+        );
+        self
+    }
+
+    /// The offset of `max`, one less than the number of faces. Kept instead of
+    /// the face count, which doesn't fit in `u128` for a full-range `u128` dial.
+    fn last(&self) -> u128 {
+        self.offset_of(self.max)
+    }
+
+    /// How many complete turns `clicks` clicks make.
+    fn full_turns(&self, clicks: u128) -> u128 {
+        self.last().checked_add(1).map_or(0, |faces| clicks / faces)
+    }
+
+    /// How many clicks turning right take the dial from `from` to `to`.
+    fn clicks_right(&self, from: u128, to: u128) -> u128 {
+        if to >= from {
+            to - from
+        } else {
+            self.last() - (from - to) + 1
+        }
+    }
+
+    /// Where the dial ends up after `clicks` clicks from `offset`.
+    fn turn(&self, offset: u128, direction: Direction, clicks: u128) -> u128 {
+        let last = self.last();
+        let clicks = last.checked_add(1).map_or(clicks, |faces| clicks % faces);
+        match direction {
+            Direction::R if clicks <= last - offset => offset + clicks,
+            Direction::R => clicks - (last - offset) - 1,
+            Direction::L if clicks <= offset => offset - clicks,
+            Direction::L => last - (clicks - offset - 1),
+        }
+    }
+
+    /// Unsigned faces subtract directly. Signed ones go through `i128`, where
+    /// the difference can wrap but its bits are still the right `u128`.
+    fn offset_of(&self, face: T) -> u128 {
+        if let (Some(face), Some(min)) = (face.to_u128(), self.min.to_u128()) {
+            return face - min;
+        }
+        let (Some(face), Some(min)) = (face.to_i128(), self.min.to_i128()) else {
+            unreachable!("faces are unsigned or fit in i128")
+        };
+        face.wrapping_sub(min).cast_unsigned()
+    }
+
+    fn face_at(&self, offset: u128) -> T {
+        let face = match self.min.to_u128() {
+            Some(min) => T::from(min + offset),
+            None => self
+                .min
+                .to_i128()
+                .and_then(|min| T::from(min.wrapping_add(offset.cast_signed()))),
+        };
+        face.expect("offset is on the dial")
+    }
+
+    pub fn events<'a>(&'a self, instructions: &'a InstructionSet) -> DialEvents<'a, T> {
+        DialEvents {
+            dial: self,
+            offset: self.offset_of(self.start),
+            instructions: instructions.0.iter(),
+        }
+    }
+
+    pub fn run(&self, instructions: &InstructionSet) -> Results<T> {
+        let mut results = Results {
+            exact: self.targets.iter().map(|t| (*t, 0)).collect(),
+            passed: self.targets.iter().map(|t| (*t, 0)).collect(),
+        };
+        for event in self.events(instructions) {
+            if let Some(exact) = results.exact.get_mut(&event.position) {
+                *exact += 1;
             }
-            if current_val == self.min.into() {
-                exact_mins += 1;
-                // passed_mins += 1;
+            for (target, clicks) in &event.clicks {
+                *results.passed.entry(*target).or_default() += clicks;
             }
-            dbg!(inst.count, current_val, exact_mins, passed_mins, "");
-        }
-        Results {
-            exact_mins,
-            passed_mins,
         }
+        results
+    }
+}
+
+/// Turns the dial one instruction at a time. Positions are tracked as an
+/// offset from `min`, so every dial works like one numbered from zero.
+pub struct DialEvents<'a, T> {
+    dial: &'a Dial<T>,
+    offset: u128,
+    instructions: std::slice::Iter<'a, Instruction>,
+}
+
+impl<T: Face> Iterator for DialEvents<'_, T> {
+    type Item = DialEvent<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let inst = self.instructions.next()?;
+        let dial = self.dial;
+        let count = u128::from(inst.count);
+        // A target `distance` clicks away in the turning direction is first
+        // reached on click `distance` (or a full turn later, if the dial is
+        // already on it) and then once every full turn after that.
+        let clicks = dial
+            .targets
+            .iter()
+            .map(|target| {
+                let target = dial.offset_of(*target);
+                let distance = match inst.direction {
+                    Direction::R => dial.clicks_right(self.offset, target),
+                    Direction::L => dial.clicks_right(target, self.offset),
+                };
+                let hits = match distance {
+                    0 => dial.full_turns(count),
+                    _ if count < distance => 0,
+                    _ => dial.full_turns(count - distance) + 1,
+                };
+                (dial.face_at(target), usize::try_from(hits).expect("hits fit in usize"))
+            })
+            .collect();
+        self.offset = dial.turn(self.offset, inst.direction, count);
+        Some(DialEvent {
+            position: dial.face_at(self.offset),
+            clicks,
+            wraps: usize::try_from(dial.full_turns(count)).expect("wraps fit in usize"),
+        })
     }
 }
 
@@ -88,21 +254,166 @@ pub struct InstructionSet(Vec<Instruction>);
 
 impl From<&str> for InstructionSet {
     fn from(value: &str) -> Self {
+        InstructionSet(value.lines().map(Instruction::from).collect())
+    }
+}
+
+/// Writes one instruction per line, the same format `From<&str>` reads.
+impl Display for InstructionSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for inst in &self.0 {
+            writeln!(f, "{inst}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Ranks solutions by total clicks, then by how many instructions they need.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+struct SolveCost {
+    clicks: usize,
+    instructions: usize,
+}
+impl Add for SolveCost {
+    type Output = SolveCost;
+
+    fn add(self, rhs: SolveCost) -> Self::Output {
+        SolveCost {
+            clicks: self.clicks + rhs.clicks,
+            instructions: self.instructions + rhs.instructions,
+        }
+    }
+}
+impl Zero for SolveCost {
+    fn zero() -> Self {
+        SolveCost {
+            clicks: 0,
+            instructions: 0,
+        }
+    }
+    fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+}
+
+impl<T: Face> Dial<T> {
+    /// The instructions that stop the dial on each of `faces` in turn, taking
+    /// the shorter way around each time.
+    pub fn solve_for_faces(&self, faces: &[T]) -> InstructionSet {
+        let mut offset = self.offset_of(self.start);
         let mut set = vec![];
-        for line in value.lines() {
-            let (direction, count) = line.split_at(1);
+        for face in faces {
+            let target = self.offset_of(*face);
+            let right = self.clicks_right(offset, target);
+            let left = self.clicks_right(target, offset);
+            let (direction, count) = if left < right {
+                (Direction::L, left)
+            } else {
+                (Direction::R, right)
+            };
             set.push(Instruction {
-                direction: Direction::from(direction),
-                count: count.parse::<u16>().unwrap(),
+                direction,
+                count: u32::try_from(count).expect("count fits in u32"),
             });
+            offset = target;
         }
         InstructionSet(set)
     }
+
+    /// The fewest clicks, and then the fewest instructions, that land the dial
+    /// on its targets exactly `hits` times. Searches click by click over which
+    /// way to turn, since rocking back and forth across a target can beat
+    /// going all the way around. `None` if the dial has no targets to hit.
+    pub fn solve_for_hits(&self, hits: usize) -> Option<InstructionSet> {
+        if hits == 0 {
+            return Some(InstructionSet(vec![]));
+        }
+        let targets: Vec<u128> = self.targets.iter().map(|t| self.offset_of(*t)).collect();
+        let targets = &targets;
+        let start = (self.offset_of(self.start), 0usize, None::<Direction>);
+        let result = dijkstra_to_goal(
+            [start],
+            |&(offset, landed, last)| {
+                [Direction::R, Direction::L]
+                    .into_iter()
+                    .map(move |direction| {
+                        let next = self.turn(offset, direction, 1);
+                        let landed = landed + usize::from(targets.contains(&next));
+                        let cost = SolveCost {
+                            clicks: 1,
+                            instructions: usize::from(last != Some(direction)),
+                        };
+                        ((next, landed, Some(direction)), cost)
+                    })
+                    .filter(|((_, landed, _), _)| *landed <= hits)
+                    .collect::<Vec<_>>()
+            },
+            |&(_, landed, _)| landed == hits,
+        );
+        let path = result.path_to_goal()?;
+        let mut set: Vec<Instruction> = vec![];
+        for (_, _, direction) in path.into_iter().skip(1) {
+            let direction = direction.expect("every step after the start turns the dial");
+            match set.last_mut() {
+                Some(inst) if inst.direction == direction => inst.count += 1,
+                _ => set.push(Instruction {
+                    direction,
+                    count: 1,
+                }),
+            }
+        }
+        Some(InstructionSet(set))
+    }
+}
+
+/// Several dials driven by one instruction file, where every line starts with
+/// the prefix of the dial it turns, as in `A:R12`.
+pub struct CombinationLock<T> {
+    dials: Vec<(String, Dial<T>)>,
+}
+
+impl<T> Default for CombinationLock<T> {
+    fn default() -> Self {
+        Self { dials: vec![] }
+    }
+}
+
+impl<T: Face> CombinationLock<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_dial(mut self, prefix: &str, dial: Dial<T>) -> Self {
+        self.dials.push((prefix.to_string(), dial));
+        self
+    }
+
+    /// Runs every dial over its own lines, keyed by prefix.
+    pub fn run(&self, instructions: &str) -> BTreeMap<String, Results<T>> {
+        let mut per_dial: BTreeMap<&str, InstructionSet> = self
+            .dials
+            .iter()
+            .map(|(prefix, _)| (prefix.as_str(), InstructionSet(vec![])))
+            .collect();
+        for line in instructions.lines() {
+            let (prefix, instruction) = line.split_once(':').expect("lines start with a dial prefix");
+            per_dial
+                .get_mut(prefix.trim())
+                .unwrap_or_else(|| panic!("unknown dial {prefix}"))
+                .0
+                .push(Instruction::from(instruction.trim()));
+        }
+        self.dials
+            .iter()
+            .map(|(prefix, dial)| (prefix.clone(), dial.run(&per_dial[prefix.as_str()])))
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use test_case::test_case;
 
     #[test]
     fn test_parse_input() {
@@ -124,19 +435,179 @@ mod tests {
         );
     }
 
+    #[test_case(50, PUZZLE_INPUT, 3, 6; "sample")]
+    #[test_case(50, "R50", 1, 1; "right onto zero")]
+    #[test_case(50, "L50", 1, 1; "left onto zero")]
+    #[test_case(50, "R1000", 0, 10; "many wraps")]
+    #[test_case(50, "L150", 1, 2; "wrap then land")]
+    #[test_case(0, "L1", 0, 0; "leaving zero left")]
+    #[test_case(0, "R1", 0, 0; "leaving zero right")]
+    #[test_case(0, "R100", 1, 1; "full turn right from zero")]
+    #[test_case(0, "L100", 1, 1; "full turn left from zero")]
+    #[test_case(0, "L0", 1, 0; "standing on zero")]
+    #[test_case(99, "R1\nL1\nR1", 2, 2; "back and forth over zero")]
+    fn test_results(start: u8, inp: &str, exact_mins: usize, passed_mins: usize) {
+        let inp: InstructionSet = inp.into();
+        let dial = Dial::new(MIN_FACE, MAX_FACE, start);
+        let actual = dial.run(&inp);
+        assert_eq!(actual.exact_hits(&MIN_FACE), exact_mins);
+        assert_eq!(actual.passed_hits(&MIN_FACE), passed_mins);
+        assert_eq!(actual.exact_total(), exact_mins);
+        assert_eq!(actual.passed_total(), passed_mins);
+    }
+
     #[test]
-    fn test_exact_mins() {
-        let inp: InstructionSet = PUZZLE_INPUT.into();
+    fn test_events() {
+        let inp: InstructionSet = "L68\nL30\nR48\nR250".into();
         let dial = Dial::new(MIN_FACE, MAX_FACE, 50);
+        let actual: Vec<(u8, usize, usize)> = dial
+            .events(&inp)
+            .map(|e| (e.position(), e.clicks_on(&MIN_FACE), e.wraps()))
+            .collect();
+        assert_eq!(actual, vec![(82, 1, 0), (52, 0, 0), (0, 1, 0), (50, 2, 2)]);
+    }
+
+    fn click_by_click<T: Face>(dial: &Dial<T>, inp: &InstructionSet) -> Vec<(T, BTreeMap<T, usize>)> {
+        let mut position = dial.start;
+        let mut out = vec![];
+        for inst in &inp.0 {
+            let mut clicks: BTreeMap<T, usize> = dial.targets.iter().map(|t| (*t, 0)).collect();
+            for _ in 0..inst.count {
+                position = match (&inst.direction, position) {
+                    (Direction::R, p) if p == dial.max => dial.min,
+                    (Direction::R, p) => p + T::one(),
+                    (Direction::L, p) if p == dial.min => dial.max,
+                    (Direction::L, p) => p - T::one(),
+                };
+                if let Some(hits) = clicks.get_mut(&position) {
+                    *hits += 1;
+                }
+            }
+            out.push((position, clicks));
+        }
+        out
+    }
+
+    #[test_case(3, 12, 7, vec![3]; "offset range")]
+    #[test_case(-5, 5, 0, vec![-5, 0, 5]; "negative faces")]
+    #[test_case(1, 1, 1, vec![1]; "single face")]
+    #[test_case(3_usize, 12, 7, vec![3, 12]; "usize faces")]
+    #[test_case(-128_i8, 127, 0, vec![-128, 127]; "full i8 range")]
+    #[test_case(u128::MAX - 9, u128::MAX, u128::MAX - 5, vec![u128::MAX]; "top of u128")]
+    #[test_case(0_u64, u64::MAX, 5, vec![0, u64::MAX]; "full u64 range")]
+    #[test_case(-70_i64, 70, 0, vec![-70, 0, 70]; "i64 across zero")]
+    #[test_case(i64::MIN, i64::MAX, 0, vec![i64::MIN, -1, 0, i64::MAX]; "full i64 range")]
+    #[test_case(0_u64, u64::MAX / 2 + 10, 0, vec![u64::MAX / 2 + 10]; "over half of u64")]
+    #[test_case(0_u128, u128::MAX, 0, vec![0, u128::MAX]; "full u128 range")]
+    #[test_case(i128::MIN, i128::MAX, -1, vec![i128::MIN, 0, i128::MAX]; "full i128 range")]
+    fn test_events_match_click_by_click<T: Face>(min: T, max: T, start: T, targets: Vec<T>) {
+        let inp: InstructionSet = PUZZLE_INPUT.into();
+        let dial = Dial::new(min, max, start).with_targets(targets);
+        let actual: Vec<(T, BTreeMap<T, usize>)> = dial
+            .events(&inp)
+            .map(|e| (e.position(), e.clicks))
+            .collect();
+        assert_eq!(actual, click_by_click(&dial, &inp));
+    }
+
+    #[test]
+    fn test_events_past_half_the_type() {
+        let top = u64::MAX / 2 + 10;
+        let dial = Dial::new(0, top, 0).with_targets([top]);
+        let actual: Vec<(u64, usize)> = dial
+            .events(&"R3\nL3\nL1\nR2".into())
+            .map(|e| (e.position(), e.clicks_on(&top)))
+            .collect();
+        assert_eq!(actual, vec![(3, 0), (0, 0), (top, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn test_full_range_dial() {
+        let dial = Dial::new(0, u64::MAX, 5).with_targets([0, u64::MAX]);
+        let actual: Vec<(u64, usize)> = dial
+            .events(&"R3\nL9\nR4".into())
+            .map(|e| (e.position(), e.clicks_on(&u64::MAX) + e.clicks_on(&0)))
+            .collect();
+        assert_eq!(actual, vec![(8, 0), (u64::MAX, 2), (3, 1)]);
+    }
+
+    #[test]
+    fn test_targets() {
+        let inp: InstructionSet = PUZZLE_INPUT.into();
+        let dial = Dial::new(MIN_FACE, MAX_FACE, 50).with_targets([0, 32, 50]);
         let actual = dial.run(&inp);
-        assert_eq!(actual.exact_mins, 3);
+        assert_eq!(actual.exact_hits(&0), 3);
+        assert_eq!(actual.passed_hits(&0), 6);
+        assert_eq!(actual.exact_hits(&32), 1);
+        assert_eq!(actual.exact_hits(&50), 0);
+        assert_eq!(actual.exact_hits(&7), 0);
     }
 
     #[test]
-    fn test_passed_mins() {
+    fn test_combination_lock() {
+        let lock = CombinationLock::new()
+            .with_dial("A", Dial::new(0u16, 99, 50))
+            .with_dial("B", Dial::new(1, 10, 1).with_targets([1, 5]));
+        let actual = lock.run("A:L68\nB:R4\nA:L30\nB: L13\nA:R48\n");
+        assert_eq!(actual["A"].exact_hits(&0), 1);
+        assert_eq!(actual["A"].passed_hits(&0), 2);
+        assert_eq!(actual["B"].exact_hits(&5), 1);
+        assert_eq!(actual["B"].exact_hits(&1), 0);
+        assert_eq!(actual["B"].passed_hits(&5), 2);
+        assert_eq!(actual["B"].passed_hits(&1), 1);
+    }
+
+    #[test]
+    fn test_instruction_set_round_trip() {
         let inp: InstructionSet = PUZZLE_INPUT.into();
+        assert_eq!(inp.to_string(), PUZZLE_INPUT);
+    }
+
+    #[test]
+    fn test_solve_for_faces() {
         let dial = Dial::new(MIN_FACE, MAX_FACE, 50);
-        let actual = dial.run(&inp);
-        assert_eq!(actual.passed_mins, 6);
+        let faces: Vec<u8> = dial
+            .events(&PUZZLE_INPUT.into())
+            .map(|e| e.position())
+            .collect();
+        let actual = dial.solve_for_faces(&faces);
+        assert_eq!(actual.to_string().lines().next(), Some("R32"));
+        assert!(actual.0.iter().all(|inst| inst.count <= 50));
+
+        let round_trip = InstructionSet::from(actual.to_string().as_str());
+        let positions: Vec<u8> = dial.events(&round_trip).map(|e| e.position()).collect();
+        assert_eq!(positions, faces);
+        assert_eq!(dial.run(&round_trip).exact_total(), 3);
+    }
+
+    #[test_case(50, 0, 0, 0)]
+    #[test_case(50, 1, 50, 1)]
+    #[test_case(50, 3, 54, 3; "passing then rocking over zero")]
+    #[test_case(1, 2, 3, 2; "next to zero")]
+    #[test_case(0, 1, 2, 2; "starting on zero")]
+    #[test_case(99, 4, 7, 4)]
+    fn test_solve_for_hits(start: u8, hits: usize, clicks: u32, instructions: usize) {
+        let dial = Dial::new(MIN_FACE, MAX_FACE, start);
+        let actual = dial.solve_for_hits(hits).unwrap();
+        assert_eq!(actual.0.iter().map(|inst| inst.count).sum::<u32>(), clicks);
+        assert_eq!(actual.0.len(), instructions);
+        let round_trip = InstructionSet::from(actual.to_string().as_str());
+        assert_eq!(dial.run(&round_trip).passed_total(), hits);
+    }
+
+    #[test]
+    fn test_solve_for_hits_text() {
+        let dial = Dial::new(MIN_FACE, MAX_FACE, 1);
+        assert_eq!(dial.solve_for_hits(2).unwrap().to_string(), "L2\nR1\n");
+    }
+
+    #[test]
+    fn test_solve_for_hits_multiple_targets() {
+        let dial = Dial::new(1, 10, 5).with_targets([3, 8]);
+        let actual = dial.solve_for_hits(4).unwrap();
+        let clicks: u32 = actual.0.iter().map(|inst| inst.count).sum();
+        assert_eq!(dial.run(&actual).passed_total(), 4);
+        assert_eq!(clicks, 2 + 2 + 2 + 2);
+        assert!(Dial::new(1, 10, 5).with_targets([]).solve_for_hits(1).is_none());
     }
 }
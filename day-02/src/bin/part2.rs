@@ -1,5 +1,12 @@
 mod shared;
 
-fn main() {
+use crate::shared::{InstructionSet, PUZZLE_INPUT, Repeats};
 
+fn main() {
+    let instruction_set = InstructionSet::from(PUZZLE_INPUT);
+    let sum: u64 = instruction_set
+        .find_invalid_ids(Repeats::AtLeastTwice)
+        .iter()
+        .sum();
+    println!("{sum}");
 }
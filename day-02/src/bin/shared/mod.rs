@@ -1,4 +1,6 @@
 #![allow(dead_code)]
+use aoc_utils::interval::{Interval, parse_intervals};
+use std::collections::BTreeSet;
 
 #[cfg(test)]
 pub const PUZZLE_INPUT: &str = include_str!("../../data/sample_input.txt");
@@ -6,14 +8,12 @@ pub const PUZZLE_INPUT: &str = include_str!("../../data/sample_input.txt");
 #[cfg(not(test))]
 pub const PUZZLE_INPUT: &str = include_str!("../../data/puzzle_input.txt");
 
-pub struct InstructionSet(Vec<Range>);
+pub struct InstructionSet(Vec<Interval<u64>>);
 
 impl From<&str> for InstructionSet {
     fn from(value: &str) -> Self {
-        let ranges: Vec<Range> = value
-            .split(',')
-            .filter_map(|v| Range::try_from(v).ok())
-            .collect();
+        let (rest, ranges) = parse_intervals(value.trim()).expect("known puzzle input");
+        assert!(rest.is_empty(), "unparsed range: {rest:?}");
         Self(ranges)
     }
 }
@@ -23,71 +23,85 @@ impl InstructionSet {
     }
 
     pub fn find_duplicates(&self) -> Vec<u64> {
-        self.0.iter().flat_map(|r| r.find_duplicates()).collect()
+        self.find_invalid_ids(Repeats::Twice)
+    }
+
+    pub fn find_invalid_ids(&self, repeats: Repeats) -> Vec<u64> {
+        self.0
+            .iter()
+            .flat_map(|r| invalid_ids(r, repeats))
+            .collect()
     }
 }
 
-struct BadRange(String);
-#[derive(PartialEq, Eq, Clone, Debug)]
-struct Range {
-    min: u64,
-    max: u64,
+/// How many times a block of digits has to repeat for an id to be invalid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Repeats {
+    Twice,
+    AtLeastTwice,
 }
 
-impl Range {
-    fn new(min: u64, max: u64) -> Self {
-        Self { min, max }
+impl Repeats {
+    fn allows(&self, count: u32) -> bool {
+        match self {
+            Repeats::Twice => count == 2,
+            Repeats::AtLeastTwice => count >= 2,
+        }
     }
+}
+
+fn digit_count(value: u64) -> u32 {
+    value.checked_ilog10().unwrap_or(0) + 1
+}
 
-    fn find_duplicates(&self) -> Vec<u64> {
-        let mut acc = vec![];
-        for i in self.min..=self.max {
-            let str_i = i.to_string();
-            let (left, right) = str_i.split_at(str_i.len() / 2);
-            if left == right {
-                acc.push(i)
+/// A `block_len` digit block repeated `count` times is the block multiplied by
+/// `1 0..01 0..01 ...`, so every invalid id of a given shape can be enumerated
+/// from the range of blocks whose product lands inside the interval.
+fn invalid_ids(range: &Interval<u64>, repeats: Repeats) -> BTreeSet<u64> {
+    let mut acc = BTreeSet::new();
+    for total_len in digit_count(range.min())..=digit_count(range.max()) {
+        for block_len in 1..total_len {
+            let count = total_len / block_len;
+            if total_len % block_len != 0 || !repeats.allows(count) {
+                continue;
             }
+            let multiplier: u64 = (0..count).map(|i| 10u64.pow(block_len * i)).sum();
+            let smallest_block = 10u64.pow(block_len - 1);
+            let largest_block = 10u64.pow(block_len) - 1;
+            let low = smallest_block.max(range.min().div_ceil(multiplier));
+            let high = largest_block.min(range.max() / multiplier);
+            acc.extend((low..=high).map(|block| block * multiplier));
         }
-        acc
-    }
-}
-impl TryFrom<&str> for Range {
-    type Error = BadRange;
-
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let (left, right): (&str, &str) = value.split_once('-').ok_or(BadRange(value.into()))?;
-        Ok(Self {
-            min: left
-                .trim_end()
-                .parse::<u64>()
-                .unwrap_or_else(|_| panic!("Bad Range: {value}")),
-            max: right
-                .trim_end()
-                .parse::<u64>()
-                .unwrap_or_else(|_| panic!("Bad Range: {value}")),
-        })
     }
+    acc
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use test_case::test_case;
+
+    #[test]
+    #[should_panic(expected = "unparsed range")]
+    fn test_malformed_range() {
+        let _ = InstructionSet::from("11-22,9x-115,998-1012\n");
+    }
 
     #[test]
     fn test_parse_input() {
         let instruction_set = InstructionSet::from(PUZZLE_INPUT);
         assert_eq!(instruction_set.len(), 11);
-        assert_eq!(instruction_set.0[0], Range::new(11, 22));
-        assert_eq!(instruction_set.0[1], Range::new(95, 115));
-        assert_eq!(instruction_set.0[2], Range::new(998, 1012));
-        assert_eq!(instruction_set.0[3], Range::new(1188511880, 1188511890));
-        assert_eq!(instruction_set.0[4], Range::new(222220, 222224));
-        assert_eq!(instruction_set.0[5], Range::new(1698522, 1698528));
-        assert_eq!(instruction_set.0[6], Range::new(446443, 446449));
-        assert_eq!(instruction_set.0[7], Range::new(38593856, 38593862));
-        assert_eq!(instruction_set.0[8], Range::new(565653, 565659));
-        assert_eq!(instruction_set.0[9], Range::new(824824821, 824824827));
-        assert_eq!(instruction_set.0[10], Range::new(2121212118, 2121212124));
+        assert_eq!(instruction_set.0[0], Interval::new(11, 22));
+        assert_eq!(instruction_set.0[1], Interval::new(95, 115));
+        assert_eq!(instruction_set.0[2], Interval::new(998, 1012));
+        assert_eq!(instruction_set.0[3], Interval::new(1188511880, 1188511890));
+        assert_eq!(instruction_set.0[4], Interval::new(222220, 222224));
+        assert_eq!(instruction_set.0[5], Interval::new(1698522, 1698528));
+        assert_eq!(instruction_set.0[6], Interval::new(446443, 446449));
+        assert_eq!(instruction_set.0[7], Interval::new(38593856, 38593862));
+        assert_eq!(instruction_set.0[8], Interval::new(565653, 565659));
+        assert_eq!(instruction_set.0[9], Interval::new(824824821, 824824827));
+        assert_eq!(instruction_set.0[10], Interval::new(2121212118, 2121212124));
     }
 
     #[test]
@@ -98,4 +112,45 @@ mod tests {
             1227775554
         );
     }
+
+    #[test]
+    fn test_find_invalid_ids_at_least_twice() {
+        let instruction_set = InstructionSet::from(PUZZLE_INPUT);
+        assert_eq!(
+            instruction_set
+                .find_invalid_ids(Repeats::AtLeastTwice)
+                .iter()
+                .sum::<u64>(),
+            4174379265
+        );
+    }
+
+    #[test_case(11, 22, Repeats::Twice, vec![11, 22])]
+    #[test_case(95, 115, Repeats::Twice, vec![99])]
+    #[test_case(95, 115, Repeats::AtLeastTwice, vec![99, 111])]
+    #[test_case(998, 1012, Repeats::AtLeastTwice, vec![999, 1010])]
+    #[test_case(1111, 1111, Repeats::AtLeastTwice, vec![1111]; "deduplicated")]
+    #[test_case(1, 9, Repeats::AtLeastTwice, vec![]; "single digits")]
+    #[test_case(565653, 565659, Repeats::AtLeastTwice, vec![565656])]
+    #[test_case(824824821, 824824827, Repeats::Twice, vec![]; "odd length")]
+    #[test_case(824824821, 824824827, Repeats::AtLeastTwice, vec![824824824])]
+    fn test_invalid_ids(min: u64, max: u64, repeats: Repeats, exp: Vec<u64>) {
+        let actual = invalid_ids(&Interval::new(min, max), repeats);
+        assert_eq!(actual.into_iter().collect::<Vec<_>>(), exp);
+    }
+
+    #[test]
+    fn test_invalid_ids_matches_brute_force() {
+        let range = Interval::new(1, 200_000);
+        let expected = (range.min()..=range.max())
+            .filter(|i| {
+                let s = i.to_string();
+                (2..=s.len()).any(|count| {
+                    s.len() % count == 0 && s == s[..s.len() / count].repeat(count)
+                })
+            })
+            .collect::<Vec<_>>();
+        let actual = invalid_ids(&range, Repeats::AtLeastTwice);
+        assert_eq!(actual.into_iter().collect::<Vec<_>>(), expected);
+    }
 }
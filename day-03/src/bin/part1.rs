@@ -2,6 +2,8 @@ mod shared;
 use shared::{PUZZLE_INPUT, InstructionSet};
 
 fn main() {
-    let out = InstructionSet::from(PUZZLE_INPUT).calculate_joltage();
+    let out = InstructionSet::from(PUZZLE_INPUT)
+        .calculate_joltage(2)
+        .expect("total joltage fits in u128");
     println!("{out}");
 }
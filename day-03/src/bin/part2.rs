@@ -1,5 +1,9 @@
 mod shared;
+use shared::{PUZZLE_INPUT, InstructionSet};
 
 fn main() {
-
+    let out = InstructionSet::from(PUZZLE_INPUT)
+        .calculate_joltage(12)
+        .expect("total joltage fits in u128");
+    println!("{out}");
 }
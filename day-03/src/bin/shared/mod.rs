@@ -21,8 +21,16 @@ impl From<&str> for InstructionSet {
     }
 }
 impl InstructionSet {
-    pub fn calculate_joltage(&self) -> usize {
-        self.0.iter().map(|b| b.calculate_joltage() as usize).sum()
+    /// The best selection of `batteries` from each bank, in bank order.
+    pub fn selections(&self, batteries: usize) -> impl Iterator<Item = Selection> + '_ {
+        self.0.iter().map(move |b| b.calculate_joltage(batteries))
+    }
+
+    /// The total joltage across every bank, or `None` if it overflows `u128`,
+    /// which a handful of banks of 38 batteries is enough to do.
+    pub fn calculate_joltage(&self, batteries: usize) -> Option<u128> {
+        self.selections(batteries)
+            .try_fold(0u128, |total, s| total.checked_add(s.joltage))
     }
 }
 
@@ -38,21 +46,47 @@ impl From<&str> for BatteryBank {
         )
     }
 }
+/// The batteries switched on in a bank, by position, and the joltage they produce.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Selection {
+    joltage: u128,
+    indices: Vec<usize>,
+}
+impl Selection {
+    pub fn joltage(&self) -> u128 {
+        self.joltage
+    }
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+}
+
 impl BatteryBank {
-    fn calculate_joltage(&self) -> u8 {
-        let mut max: (usize, u8) = (0, 0);
-        for (idx, i) in self.0[0..self.0.len() - 1].iter().enumerate() {
-            if i > &max.1 {
-                max = (idx, *i)
+    /// Picks the `batteries` digits, in order, that form the largest number.
+    /// A digit is dropped whenever a larger one follows it and there are still
+    /// enough digits left to fill the selection. The joltage fits up to 38
+    /// batteries.
+    fn calculate_joltage(&self, batteries: usize) -> Selection {
+        assert!(
+            batteries <= self.0.len(),
+            "bank only has {} batteries",
+            self.0.len()
+        );
+        assert!(batteries <= 38, "joltage of {batteries} batteries overflows");
+        let mut droppable = self.0.len() - batteries;
+        let mut indices: Vec<usize> = Vec::with_capacity(self.0.len());
+        for (idx, digit) in self.0.iter().enumerate() {
+            while droppable > 0 && indices.last().is_some_and(|&last| self.0[last] < *digit) {
+                indices.pop();
+                droppable -= 1;
             }
+            indices.push(idx);
         }
-        let mut second: (usize, u8) = (0, 0);
-        for (idx, i) in self.0[max.0 + 1..self.0.len()].iter().enumerate() {
-            if i > &second.1 {
-                second = (idx, *i)
-            }
-        }
-        (max.1 * 10) + second.1
+        indices.truncate(batteries);
+        let joltage = indices
+            .iter()
+            .fold(0u128, |acc, &idx| acc * 10 + u128::from(self.0[idx]));
+        Selection { joltage, indices }
     }
 }
 
@@ -66,17 +100,51 @@ mod tests {
         let actual = InstructionSet::from(PUZZLE_INPUT);
         assert_eq!(actual.len(), 4);
     }
-    #[test_case("987654321111111", 98)]
-    #[test_case("811111111111119", 89)]
-    #[test_case("234234234234278", 78)]
-    #[test_case("818181911112111", 92)]
-    fn test_bank_joltage(inp: &str, exp: u8) {
+    #[test_case("987654321111111", 2, 98)]
+    #[test_case("811111111111119", 2, 89)]
+    #[test_case("234234234234278", 2, 78)]
+    #[test_case("818181911112111", 2, 92)]
+    #[test_case("987654321111111", 12, 987654321111)]
+    #[test_case("811111111111119", 12, 811111111119)]
+    #[test_case("234234234234278", 12, 434234234278)]
+    #[test_case("818181911112111", 12, 888911112111)]
+    #[test_case("12345", 5, 12345; "every battery")]
+    #[test_case("12345", 0, 0; "no batteries")]
+    #[test_case("9876543219876543211111", 20, 98765439876543211111; "past u64")]
+    fn test_bank_joltage(inp: &str, batteries: usize, exp: u128) {
         let bank = BatteryBank::from(inp);
-        assert_eq!(bank.calculate_joltage(), exp);
+        assert_eq!(bank.calculate_joltage(batteries).joltage(), exp);
+    }
+
+    #[test]
+    fn test_bank_selection_indices() {
+        let bank = BatteryBank::from("818181911112111");
+        assert_eq!(bank.calculate_joltage(2).indices(), &[6, 11]);
+        assert_eq!(
+            bank.calculate_joltage(12).indices(),
+            &[0, 2, 4, 6, 7, 8, 9, 10, 11, 12, 13, 14]
+        );
+    }
+
+    #[test_case(2, 357)]
+    #[test_case(12, 3121910778619)]
+    fn test_inp_joltage(batteries: usize, exp: u128) {
+        let actual = InstructionSet::from(PUZZLE_INPUT); assert_eq!(actual.calculate_joltage(batteries), Some(exp));
     }
 
     #[test]
-    fn test_inp_joltage() {
-        let actual = InstructionSet::from(PUZZLE_INPUT); assert_eq!(actual.calculate_joltage(), 357);
+    fn test_inp_selections() {
+        let actual: Vec<Vec<usize>> = InstructionSet::from(PUZZLE_INPUT)
+            .selections(2)
+            .map(|s| s.indices().to_vec())
+            .collect();
+        assert_eq!(actual, vec![vec![0, 1], vec![0, 14], vec![13, 14], vec![6, 11]]);
+    }
+
+    #[test_case(3, Some(3 * (10u128.pow(38) - 1)); "fits")]
+    #[test_case(4, None; "overflows")]
+    fn test_total_joltage_overflow(banks: usize, exp: Option<u128>) {
+        let inp = format!("{}\n", "9".repeat(38)).repeat(banks);
+        assert_eq!(InstructionSet::from(inp.as_str()).calculate_joltage(38), exp);
     }
 }
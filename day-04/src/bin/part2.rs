@@ -1,5 +1,7 @@
 mod shared;
+use shared::{PUZZLE_INPUT, Warehouse};
 
 fn main() {
-
+    let warehouse = Warehouse::from(PUZZLE_INPUT);
+    println!("{}", warehouse.remove_accessible(false).total_removed())
 }
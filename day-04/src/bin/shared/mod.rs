@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 use rayon::prelude::*;
-use aoc_utils::{Collection, Direction, ParseableCharacters, Tile, parse_collection};
+use aoc_utils::{Collection, Loc, Neighborhood, Tile, parse_collection};
+use std::collections::HashMap;
 
 #[cfg(test)]
 pub const PUZZLE_INPUT: &str = include_str!("../../data/sample_input.txt");
@@ -8,25 +9,13 @@ pub const PUZZLE_INPUT: &str = include_str!("../../data/sample_input.txt");
 #[cfg(not(test))]
 pub const PUZZLE_INPUT: &str = include_str!("../../data/puzzle_input.txt");
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Tile)]
 pub enum WarehouseSlot {
+    #[tile('@')]
     Paper,
+    #[tile('.')]
     Empty,
 }
-impl ParseableCharacters for WarehouseSlot {
-    fn valid_chars() -> Vec<char> {
-        vec!['@', '.']
-    }
-}
-impl From<char> for WarehouseSlot {
-    fn from(value: char) -> Self {
-        match value {
-            '@' => Self::Paper,
-            '.' => Self::Empty,
-            _ => unreachable!(),
-        }
-    }
-}
 
 pub struct Warehouse(Collection<WarehouseSlot>);
 impl From<&str> for Warehouse {
@@ -41,21 +30,87 @@ impl Warehouse {
             .par_iter()
             .filter(|t| t.get_type() == &WarehouseSlot::Paper)
             .filter(|t| {
-                Direction::get_all()
-                    .par_iter()
-                    .filter(|d| {
-                        t.loc().get_nearby(**d, 1).is_some_and(|x| {
-                            self.0.get_tile(x).is_some_and(|neighbor| {
-                                neighbor.get_type() == &WarehouseSlot::Paper
-                            })
-                        })
+                self.0
+                    .count_neighbors_matching(*t.loc(), &Neighborhood::All, |neighbor| {
+                        neighbor.get_type() == &WarehouseSlot::Paper
                     })
-                    .count()
                     < 4
             })
             .cloned()
             .collect()
     }
+
+    /// Keeps taking away every accessible roll until none are left. Rather than
+    /// rescanning the warehouse, each roll's paper neighbor count is decremented
+    /// as its neighbors go, and it joins the next wave once the count drops below 4.
+    pub fn remove_accessible(&self, record_frames: bool) -> Removal {
+        let is_paper = |t: &Tile<WarehouseSlot>| t.get_type() == &WarehouseSlot::Paper;
+        let mut counts: HashMap<Loc, usize> = self
+            .0
+            .tiles()
+            .iter()
+            .filter(|t| is_paper(t))
+            .map(|t| {
+                let count = self.0.count_neighbors_matching(*t.loc(), &Neighborhood::All, is_paper);
+                (*t.loc(), count)
+            })
+            .collect();
+        let mut wave: Vec<Loc> = counts
+            .iter()
+            .filter(|(_, count)| **count < 4)
+            .map(|(loc, _)| *loc)
+            .collect();
+
+        let mut state = self.0.clone();
+        let mut removal = Removal::default();
+        if record_frames {
+            removal.frames.push(state.clone());
+        }
+        while !wave.is_empty() {
+            for loc in &wave {
+                counts.remove(loc);
+                if let Some(tile) = state.get_tile_mut(*loc) {
+                    tile.set_type(WarehouseSlot::Empty);
+                }
+            }
+            let mut next = vec![];
+            for loc in &wave {
+                for (_, neighbor) in self.0.neighbors(*loc, &Neighborhood::All) {
+                    if let Some(count) = counts.get_mut(neighbor.loc()) {
+                        *count -= 1;
+                        if *count == 3 {
+                            next.push(*neighbor.loc());
+                        }
+                    }
+                }
+            }
+            removal.waves.push(wave.len());
+            if record_frames {
+                removal.frames.push(state.clone());
+            }
+            wave = next;
+        }
+        removal
+    }
+}
+
+/// How many rolls each wave of `Warehouse::remove_accessible` took away, and,
+/// when recorded, the warehouse before the first wave and after every one.
+#[derive(Default, Debug)]
+pub struct Removal {
+    waves: Vec<usize>,
+    frames: Vec<Collection<WarehouseSlot>>,
+}
+impl Removal {
+    pub fn total_removed(&self) -> usize {
+        self.waves.iter().sum()
+    }
+    pub fn waves(&self) -> &[usize] {
+        &self.waves
+    }
+    pub fn frames(&self) -> &[Collection<WarehouseSlot>] {
+        &self.frames
+    }
 }
 
 #[cfg(test)]
@@ -65,6 +120,30 @@ mod tests {
     #[test]
     fn test_parse_input() {
         let actual = Warehouse::from(PUZZLE_INPUT);
-        // assert_eq!(actual.accessible_slots().len(), 13);
+        assert_eq!(actual.accessible_slots().len(), 13);
+    }
+
+    #[test]
+    fn test_remove_accessible() {
+        let actual = Warehouse::from(PUZZLE_INPUT).remove_accessible(false);
+        assert_eq!(actual.waves(), &[13, 12, 7, 5, 2, 1, 1, 1, 1]);
+        assert_eq!(actual.total_removed(), 43);
+        assert!(actual.frames().is_empty());
+    }
+
+    #[test]
+    fn test_remove_accessible_frames() {
+        let warehouse = Warehouse::from(PUZZLE_INPUT);
+        let paper = warehouse.0.count_tile_type(&WarehouseSlot::Paper);
+        let actual = warehouse.remove_accessible(true);
+        assert_eq!(actual.frames().len(), actual.waves().len() + 1);
+        assert_eq!(actual.frames()[0].to_string(), PUZZLE_INPUT);
+        let mut remaining = paper;
+        for (frame, removed) in actual.frames()[1..].iter().zip(actual.waves()) {
+            remaining -= removed;
+            assert_eq!(frame.count_tile_type(&WarehouseSlot::Paper), remaining);
+        }
+        let last = Warehouse(actual.frames().last().unwrap().clone());
+        assert!(last.accessible_slots().is_empty());
     }
 }
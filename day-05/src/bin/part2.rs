@@ -1,5 +1,7 @@
 mod shared;
+use shared::{InstructionSet, PUZZLE_INPUT};
 
 fn main() {
-
+    let actual = InstructionSet::from(PUZZLE_INPUT);
+    println!("{}", actual.count_fresh_ids());
 }
@@ -1,4 +1,5 @@
 #![allow(dead_code)]
+use aoc_utils::interval::{IntervalSet, parse_interval_set};
 
 #[cfg(test)]
 pub const PUZZLE_INPUT: &str = include_str!("../../data/sample_input.txt");
@@ -9,37 +10,17 @@ pub const PUZZLE_INPUT: &str = include_str!("../../data/puzzle_input.txt");
 #[derive(PartialEq, Eq, Clone, Debug, PartialOrd)]
 pub struct IngredientId(usize);
 
-#[derive(PartialEq, Eq, Clone, Debug)]
-struct Range {
-    min: IngredientId,
-    max: IngredientId,
-}
-
-impl Range {
-    fn new(min: usize, max: usize) -> Self {
-        Self {
-            min: IngredientId(min),
-            max: IngredientId(max),
-        }
-    }
-}
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub struct InstructionSet {
-    fresh_ranges: Vec<Range>,
+    fresh_ranges: IntervalSet<usize>,
     available_ids: Vec<IngredientId>,
 }
 
 impl From<&str> for InstructionSet {
     fn from(value: &str) -> Self {
         let parts = value.split_once("\n\n").expect("known puzzle input");
-        let fresh_ranges = parts
-            .0
-            .lines()
-            .map(|l| l.split_once('-').unwrap())
-            .map(|(min, max)| {
-                Range::new(min.parse::<usize>().unwrap(), max.parse::<usize>().unwrap())
-            })
-            .collect();
+        let (rest, fresh_ranges) = parse_interval_set(parts.0).expect("known puzzle input");
+        assert!(rest.trim().is_empty(), "unparsed fresh range: {rest:?}");
         let available_ids = parts
             .1
             .lines()
@@ -49,7 +30,7 @@ impl From<&str> for InstructionSet {
     }
 }
 impl InstructionSet {
-    fn new(fresh_ranges: Vec<Range>, available_ids: Vec<IngredientId>) -> Self {
+    fn new(fresh_ranges: IntervalSet<usize>, available_ids: Vec<IngredientId>) -> Self {
         Self {
             fresh_ranges,
             available_ids,
@@ -59,27 +40,48 @@ impl InstructionSet {
     pub fn get_fresh(&self) -> Vec<IngredientId> {
         self.available_ids
             .iter()
-            .filter(|id| {
-                for range in self.fresh_ranges.iter() {
-                    let is_valid = **id >= range.min && **id <= range.max;
-                    if is_valid {
-                        return true;
-                    }
-                }
-                false
-            })
+            .filter(|id| self.fresh_ranges.contains(&id.0))
             .cloned()
             .collect()
     }
+
+    pub fn count_fresh_ids(&self) -> usize {
+        self.fresh_ranges
+            .total_len()
+            .expect("fresh ID count fits in usize")
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use test_case::test_case;
 
     #[test]
     fn test_parse_input() {
         let actual = InstructionSet::from(PUZZLE_INPUT);
         assert_eq!(actual.get_fresh().len(), 3);
     }
+
+    #[test]
+    fn test_count_fresh_ids() {
+        let actual = InstructionSet::from(PUZZLE_INPUT);
+        assert_eq!(actual.count_fresh_ids(), 14);
+    }
+
+    #[test_case("1-3\n4-6\n\n3\n4\n7\n", 2, 6; "adjacent")]
+    #[test_case("1-10\n3-4\n\n4\n10\n11\n", 2, 10; "nested")]
+    #[test_case("5-8\n5-8\n\n5\n9\n", 1, 4; "duplicate")]
+    #[test_case("10-12\n1-2\n11-20\n\n2\n15\n21\n", 2, 13; "unsorted overlap")]
+    fn test_merged_ranges(inp: &str, exp_fresh: usize, exp_count: usize) {
+        let actual = InstructionSet::from(inp);
+        assert_eq!(actual.get_fresh().len(), exp_fresh);
+        assert_eq!(actual.count_fresh_ids(), exp_count);
+    }
+
+    #[test]
+    #[should_panic(expected = "unparsed fresh range")]
+    fn test_malformed_range() {
+        let _ = InstructionSet::from("3-5\n10-1x\n16-20\n\n1\n");
+    }
 }
@@ -2,5 +2,5 @@ mod shared;
 use shared::{PUZZLE_INPUT, Homework};
 
 fn main() {
-    println!("{}", Homework::from(PUZZLE_INPUT).solve());
+    println!("{}", Homework::from(PUZZLE_INPUT).solve().expect("homework overflowed"));
 }
@@ -1,5 +1,7 @@
 mod shared;
+use shared::{PUZZLE_INPUT, Homework, ReadingOrder};
 
 fn main() {
-
+    let homework = Homework::parse(PUZZLE_INPUT, ReadingOrder::Columns);
+    println!("{}", homework.solve().expect("homework overflowed"));
 }
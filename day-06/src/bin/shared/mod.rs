@@ -1,5 +1,6 @@
 #![allow(dead_code)]
 
+use std::ops::Range;
 use std::str::FromStr;
 
 #[cfg(test)]
@@ -8,49 +9,66 @@ pub const PUZZLE_INPUT: &str = include_str!("../../data/sample_input.txt");
 #[cfg(not(test))]
 pub const PUZZLE_INPUT: &str = include_str!("../../data/puzzle_input.txt");
 
+/// How the digits of each problem are laid out inside its block of columns.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ReadingOrder {
+    /// Each line holds one number.
+    Rows,
+    /// Each column holds one number, read top to bottom, starting from the
+    /// rightmost column.
+    Columns,
+}
+
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub struct Homework(Vec<Problem>);
 
 impl From<&str> for Homework {
     fn from(value: &str) -> Self {
-        let problem_numbers: Vec<Vec<usize>> = value
-            .lines()
-            .rev()
-            .skip(1)
-            .map(|l| {
-                l.split_whitespace()
-                    .map(|n| n.parse::<usize>().unwrap())
-                    .collect()
-            })
-            .collect();
-        let problem_ops: Vec<Operation> = value
-            .lines()
-            .last()
-            .map(|l| {
-                l.split_whitespace()
-                    .map(|op| Operation::from_str(op).unwrap())
-                    .collect()
-            })
-            .unwrap();
-        let mut problems: Vec<Problem> = vec![];
-        for (i, op) in problem_ops.iter().enumerate() {
-            let numbers: Vec<usize> = problem_numbers.iter().map(|row| row[i]).collect();
-            problems.push(Problem::new(numbers, op.clone()))
+        Self::parse(value, ReadingOrder::Rows)
+    }
+}
+impl Homework {
+    /// Splits the worksheet into problems wherever a column is blank on every
+    /// line, including the operator line.
+    pub fn parse(value: &str, order: ReadingOrder) -> Self {
+        let lines: Vec<Vec<char>> = value.lines().map(|l| l.chars().collect()).collect();
+        let width = lines.iter().map(Vec::len).max().unwrap_or(0);
+        let blank = |col: usize| lines.iter().all(|l| char_at(l, col) == ' ');
+        let (op_line, number_lines) = lines.split_last().expect("known puzzle input");
 
+        let mut problems: Vec<Problem> = vec![];
+        let mut start = 0;
+        for col in 0..=width {
+            if col == width || blank(col) {
+                if col > start {
+                    problems.push(Problem::from_block(number_lines, op_line, start..col, order));
+                }
+                start = col + 1;
+            }
         }
         Self(problems)
     }
-}
-impl Homework {
-    pub fn solve(&self) -> usize {
-        self.0.iter().map(|p| p.solve()).sum()
+
+    /// The grand total, or `None` if any problem overflows or goes negative.
+    pub fn solve(&self) -> Option<usize> {
+        self.0
+            .iter()
+            .try_fold(0usize, |acc, p| acc.checked_add(p.solve()?))
     }
 }
 
+fn char_at(line: &[char], col: usize) -> char {
+    line.get(col).copied().unwrap_or(' ')
+}
+
 #[derive(PartialEq, Eq, Clone, Debug)]
 enum Operation {
     Add,
+    Subtract,
     Multiply,
+    Concat,
+    Min,
+    Max,
 }
 
 #[derive(Debug)]
@@ -62,12 +80,32 @@ impl FromStr for Operation {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "+" => Ok(Self::Add),
+            "-" => Ok(Self::Subtract),
             "*" => Ok(Self::Multiply),
+            "|" => Ok(Self::Concat),
+            "<" => Ok(Self::Min),
+            ">" => Ok(Self::Max),
             _ => Err(InvalidChar),
         }
     }
 }
 
+impl Operation {
+    fn apply(&self, left: usize, right: usize) -> Option<usize> {
+        match self {
+            Operation::Add => left.checked_add(right),
+            Operation::Subtract => left.checked_sub(right),
+            Operation::Multiply => left.checked_mul(right),
+            Operation::Concat => {
+                let shift = 10usize.checked_pow(right.checked_ilog10().unwrap_or(0) + 1)?;
+                left.checked_mul(shift)?.checked_add(right)
+            }
+            Operation::Min => Some(left.min(right)),
+            Operation::Max => Some(left.max(right)),
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Clone, Debug)]
 struct Problem {
     numbers: Vec<usize>,
@@ -78,21 +116,88 @@ impl Problem {
     fn new(numbers: Vec<usize>, op: Operation) -> Self {
         Self { numbers, op }
     }
-    fn solve(&self) -> usize {
-        match self.op {
-            Operation::Add => self.numbers.iter().sum(),
-            Operation::Multiply => self.numbers.iter().product()
-        }
+
+    fn from_block(
+        number_lines: &[Vec<char>],
+        op_line: &[char],
+        columns: Range<usize>,
+        order: ReadingOrder,
+    ) -> Self {
+        let op: String = columns
+            .clone()
+            .map(|col| char_at(op_line, col))
+            .filter(|c| *c != ' ')
+            .collect();
+        let op = Operation::from_str(&op).expect("known puzzle input");
+        let digit_runs: Vec<String> = match order {
+            ReadingOrder::Rows => number_lines
+                .iter()
+                .map(|l| columns.clone().map(|col| char_at(l, col)).collect())
+                .collect(),
+            ReadingOrder::Columns => columns
+                .rev()
+                .map(|col| number_lines.iter().map(|l| char_at(l, col)).collect())
+                .collect(),
+        };
+        let numbers = digit_runs
+            .iter()
+            .map(|run| run.trim())
+            .filter(|run| !run.is_empty())
+            .map(|run| run.parse::<usize>().unwrap())
+            .collect();
+        Self::new(numbers, op)
+    }
+
+    /// Folds the numbers left to right, or `None` on overflow, on going below
+    /// zero, or when there are no numbers to fold.
+    fn solve(&self) -> Option<usize> {
+        let (first, rest) = self.numbers.split_first()?;
+        rest.iter()
+            .try_fold(*first, |acc, n| self.op.apply(acc, *n))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use test_case::test_case;
 
     #[test]
     fn test_parse_input() {
         let actual = Homework::from(PUZZLE_INPUT);
-        assert_eq!(actual.solve(), 4277556);
+        assert_eq!(actual.solve(), Some(4277556));
+    }
+
+    #[test]
+    fn test_parse_columns() {
+        let actual = Homework::parse(PUZZLE_INPUT, ReadingOrder::Columns);
+        assert_eq!(actual.0[0], Problem::new(vec![356, 24, 1], Operation::Multiply));
+        assert_eq!(actual.0[3], Problem::new(vec![4, 431, 623], Operation::Add));
+        assert_eq!(actual.solve(), Some(3263827));
+    }
+
+    #[test]
+    fn test_parse_ragged_lines() {
+        let actual = Homework::parse("12   7\n3   45\n-   |\n", ReadingOrder::Rows);
+        assert_eq!(
+            actual.0,
+            vec![
+                Problem::new(vec![12, 3], Operation::Subtract),
+                Problem::new(vec![7, 45], Operation::Concat),
+            ]
+        );
+    }
+
+    #[test_case(vec![10, 3, 2], Operation::Subtract, Some(5))]
+    #[test_case(vec![3, 10], Operation::Subtract, None; "below zero")]
+    #[test_case(vec![12, 0, 345], Operation::Concat, Some(120345))]
+    #[test_case(vec![4, 9, 2], Operation::Min, Some(2))]
+    #[test_case(vec![4, 9, 2], Operation::Max, Some(9))]
+    #[test_case(vec![usize::MAX, 1], Operation::Add, None; "add overflow")]
+    #[test_case(vec![usize::MAX, 2], Operation::Multiply, None; "multiply overflow")]
+    #[test_case(vec![usize::MAX, 1], Operation::Concat, None; "concat overflow")]
+    #[test_case(vec![], Operation::Add, None; "no numbers")]
+    fn test_solve(numbers: Vec<usize>, op: Operation, exp: Option<usize>) {
+        assert_eq!(Problem::new(numbers, op).solve(), exp);
     }
 }
@@ -1,5 +1,7 @@
 mod shared;
+use shared::{ Manifold, PUZZLE_INPUT };
 
 fn main() {
-
+    let manifold = Manifold::from(PUZZLE_INPUT);
+    println!("{}", manifold.count_timelines())
 }
@@ -1,5 +1,6 @@
 #![allow(dead_code)]
-use aoc_utils::{Collection, Direction, Loc, ParseableCharacters, Tile, parse_collection};
+use aoc_utils::{Collection, Loc, Neighborhood, Tile, parse_collection};
+use std::fmt::Display;
 
 #[cfg(test)]
 pub const PUZZLE_INPUT: &str = include_str!("../../data/sample_input.txt");
@@ -13,32 +14,18 @@ pub enum Status {
     Deactivated,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Tile)]
 pub enum ManifoldSlot {
+    #[tile('.')]
     Empty,
+    #[tile('S')]
     Start,
+    #[tile('^', default = Status::Deactivated)]
     Splitter(Status),
+    #[tile('|')]
     Beam,
 }
 
-impl ParseableCharacters for ManifoldSlot {
-    fn valid_chars() -> Vec<char> {
-        vec!['.', 'S', '^', '|']
-    }
-}
-
-impl From<char> for ManifoldSlot {
-    fn from(value: char) -> Self {
-        match value {
-            '.' => Self::Empty,
-            'S' => Self::Start,
-            '^' => Self::Splitter(Status::Deactivated),
-            '|' => Self::Beam,
-            _ => unreachable!(),
-        }
-    }
-}
-
 pub struct Manifold(Collection<ManifoldSlot>);
 
 impl From<&str> for Manifold {
@@ -47,6 +34,12 @@ impl From<&str> for Manifold {
     }
 }
 
+impl Display for Manifold {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 impl Manifold {
     fn count(&self, manifold_type: ManifoldSlot) -> usize {
         self.0.count_tile_type(&manifold_type)
@@ -64,12 +57,62 @@ impl Manifold {
         let mut beams: Vec<&Tile<ManifoldSlot>> = vec![start];
         let mut activated_splitters: Vec<Loc> = vec![];
 
+        let mut path: Vec<Loc> = vec![];
+
         while !beams.is_empty() {
             (beams, activated_splitters) = self.move_beams(beams, activated_splitters);
+            path.extend(beams.iter().map(|t| *t.loc()));
+        }
+        for loc in path {
+            let tile = self.0.get_tile_mut(loc).unwrap();
+            if tile.get_type() == &ManifoldSlot::Empty {
+                tile.set_type(ManifoldSlot::Beam);
+            }
+        }
+        for loc in &activated_splitters {
+            self.0
+                .get_tile_mut(*loc)
+                .unwrap()
+                .set_type(ManifoldSlot::Splitter(Status::Activated));
         }
         activated_splitters.len()
     }
 
+    /// How many distinct paths a single particle can take from the start to
+    /// the bottom row. Rather than following each path, every column carries
+    /// the number of timelines passing through it, and each row moves those
+    /// counts straight down or, at a splitter, to both sides. A splitter on
+    /// the edge sends one side out of the manifold, which ends that timeline.
+    pub fn count_timelines(&self) -> u128 {
+        let start = self.find_start().unwrap();
+        let width = self.0.count_columns();
+        let mut exited: u128 = 0;
+        let mut timelines: Vec<u128> = vec![0; width];
+        timelines[start.loc().get_x() as usize] = 1;
+
+        for y in start.loc().get_y() + 1..self.0.count_rows() as isize {
+            let mut next: Vec<u128> = vec![0; width];
+            for (tile, count) in self.0.row_slice(y).iter().zip(&timelines) {
+                let x = tile.loc().get_x() as usize;
+                match tile.get_type() {
+                    ManifoldSlot::Splitter(_) => {
+                        match x.checked_sub(1) {
+                            Some(left) => next[left] += count,
+                            None => exited += count,
+                        }
+                        match next.get_mut(x + 1) {
+                            Some(right) => *right += count,
+                            None => exited += count,
+                        }
+                    }
+                    _ => next[x] += count,
+                }
+            }
+            timelines = next;
+        }
+        exited + timelines.iter().sum::<u128>()
+    }
+
     fn move_beams<'a>(
         &'a self,
         beams: Vec<&Tile<ManifoldSlot>>,
@@ -77,27 +120,22 @@ impl Manifold {
     ) -> (Vec<&'a Tile<ManifoldSlot>>, Vec<Loc>) {
         let mut new_beams: Vec<Loc> = vec![];
         let mut activated_splitters: Vec<Loc> = activated_splitters.clone();
+        let below = Neighborhood::Stencil(vec![(0, 1)]);
+        let sideways = Neighborhood::Stencil(vec![(-1, 0), (1, 0)]);
         for beam in beams {
-            let next_tile = self
-                .0
-                .get_tile(beam.loc().get_nearby(Direction::South, 1).unwrap());
-            if let Some(t) = next_tile {
+            for (_, t) in self.0.neighbors(*beam.loc(), &below) {
                 match t.get_type() {
-                    ManifoldSlot::Splitter(Status::Deactivated) => {
+                    ManifoldSlot::Splitter(_) => {
                         if !activated_splitters.contains(t.loc()) {
                             activated_splitters.push(*t.loc());
                         }
-                        let left = t.loc().get_nearby(Direction::West, 1).unwrap();
-                        let right = t.loc().get_nearby(Direction::East, 1).unwrap();
-
-                        if !new_beams.contains(&left) {
-                            new_beams.push(left);
-                        }
-                        if !new_beams.contains(&right) {
-                            new_beams.push(right);
+                        for (_, side) in self.0.neighbors(*t.loc(), &sideways) {
+                            if !new_beams.contains(side.loc()) {
+                                new_beams.push(*side.loc());
+                            }
                         }
                     }
-                    ManifoldSlot::Empty => new_beams.push(*t.loc()),
+                    ManifoldSlot::Empty | ManifoldSlot::Beam => new_beams.push(*t.loc()),
                     _ => continue,
                 }
             }
@@ -112,6 +150,7 @@ impl Manifold {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use test_case::test_case;
 
     #[test]
     fn test_parse_input() {
@@ -130,4 +169,43 @@ mod tests {
         let mut actual = Manifold::from(PUZZLE_INPUT);
         assert_eq!(actual.fire_beam(), 21);
     }
+
+    #[test_case(PUZZLE_INPUT, 40; "sample")]
+    #[test_case("S..\n^..\n...\n", 2; "splitter on the left edge")]
+    #[test_case("..S\n..^\n.^.\n", 3; "splitter on the right edge")]
+    #[test_case("S\n^\n", 2; "single column")]
+    fn test_count_timelines(inp: &str, exp: u128) {
+        let actual = Manifold::from(inp);
+        assert_eq!(actual.count_timelines(), exp);
+    }
+
+    #[test]
+    fn test_fire_beam_marks_path() {
+        let expected = "\
+.......S.......
+.......|.......
+......|^|......
+......|.|......
+.....|^|^|.....
+.....|.|.|.....
+....|^|^|^|....
+....|.|.|.|....
+...|^|^|||^|...
+...|.|.|||.|...
+..|^|^|||^|^|..
+..|.|.|||.|.|..
+.|^|||^||.||^|.
+.|.|||.||.||.|.
+|^|^|^|^|^|||^|
+|.|.|.|.|.|||.|
+";
+        let mut actual = Manifold::from(PUZZLE_INPUT);
+        actual.fire_beam();
+        assert_eq!(actual.to_string(), expected);
+        assert_eq!(actual.count(ManifoldSlot::Beam), expected.matches('|').count());
+        assert_eq!(actual.count(ManifoldSlot::Splitter(Status::Activated)), 21);
+        assert_eq!(actual.count(ManifoldSlot::Splitter(Status::Deactivated)), 1);
+        assert_eq!(actual.fire_beam(), 21);
+        assert_eq!(actual.to_string(), expected);
+    }
 }
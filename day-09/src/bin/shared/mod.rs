@@ -1,4 +1,5 @@
 #![allow(dead_code)]
+use aoc_utils::search::bfs;
 
 #[cfg(test)]
 pub const PUZZLE_INPUT: &str = include_str!("../../data/sample_input.txt");
@@ -36,6 +37,117 @@ impl InstructionSet {
             .max()
             .unwrap()
     }
+
+    /// The largest rectangle with red corners whose every tile is red or green,
+    /// meaning on or inside the loop the red tiles trace out.
+    pub fn get_largest_enclosed_area(&self) -> usize {
+        let floor = CompressedFloor::new(&self.0);
+        self.0
+            .iter()
+            .enumerate()
+            .flat_map(|(i, t)| {
+                self.0
+                    .iter()
+                    .skip(i + 1)
+                    .filter(|t2| floor.is_enclosed(t, t2))
+                    .map(|t2| t.get_area_between(t2))
+                    .collect::<Vec<usize>>()
+            })
+            .max()
+            .unwrap()
+    }
+}
+
+/// The floor squeezed down so every distinct red x and y, and every gap of at
+/// least one tile between neighbouring ones, becomes a single cell, with an
+/// extra ring of cells around the edge so the outside is connected. Cells
+/// reachable from that ring without crossing the loop are outside, and a prefix
+/// sum over them answers whether a rectangle touches the outside in constant time.
+struct CompressedFloor {
+    xs: Axis,
+    ys: Axis,
+    outside_prefix: Vec<Vec<usize>>,
+}
+
+/// The distinct coordinates along one axis and the compressed cell each maps to.
+struct Axis {
+    coords: Vec<usize>,
+    cells: Vec<usize>,
+    len: usize,
+}
+
+impl Axis {
+    fn new(mut coords: Vec<usize>) -> Self {
+        coords.sort_unstable();
+        coords.dedup();
+        let mut cells = Vec::with_capacity(coords.len());
+        let mut cell = 1;
+        for (i, coord) in coords.iter().enumerate() {
+            // Neighbours that touch get no gap cell, since no tile lies between them.
+            if i > 0 {
+                cell += if coord - coords[i - 1] > 1 { 2 } else { 1 };
+            }
+            cells.push(cell);
+        }
+        Self {
+            coords,
+            cells,
+            len: cell + 2,
+        }
+    }
+
+    fn cell(&self, coord: usize) -> usize {
+        self.cells[self.coords.binary_search(&coord).unwrap()]
+    }
+}
+
+impl CompressedFloor {
+    fn new(red_tiles: &[Tile]) -> Self {
+        let mut floor = Self {
+            xs: Axis::new(red_tiles.iter().map(|t| t.x).collect()),
+            ys: Axis::new(red_tiles.iter().map(|t| t.y).collect()),
+            outside_prefix: vec![],
+        };
+        let (width, height) = (floor.xs.len, floor.ys.len);
+
+        let mut on_loop = vec![vec![false; width]; height];
+        for (a, b) in red_tiles.iter().zip(red_tiles.iter().cycle().skip(1)) {
+            let (ax, ay) = floor.cell(a);
+            let (bx, by) = floor.cell(b);
+            for row in on_loop.iter_mut().take(ay.max(by) + 1).skip(ay.min(by)) {
+                row[ax.min(bx)..=ax.max(bx)].fill(true);
+            }
+        }
+
+        let outside = bfs([(0usize, 0usize)], |&(x, y)| {
+            [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)]
+                .into_iter()
+                .filter(|&(nx, ny)| nx < width && ny < height && !on_loop[ny][nx])
+                .collect::<Vec<_>>()
+        });
+        let mut prefix = vec![vec![0; width + 1]; height + 1];
+        for y in 0..height {
+            for x in 0..width {
+                let cell = usize::from(outside.distance(&(x, y)).is_some());
+                prefix[y + 1][x + 1] = cell + prefix[y][x + 1] + prefix[y + 1][x] - prefix[y][x];
+            }
+        }
+        floor.outside_prefix = prefix;
+        floor
+    }
+
+    fn cell(&self, tile: &Tile) -> (usize, usize) {
+        (self.xs.cell(tile.x), self.ys.cell(tile.y))
+    }
+
+    fn is_enclosed(&self, corner: &Tile, opposite: &Tile) -> bool {
+        let (ax, ay) = self.cell(corner);
+        let (bx, by) = self.cell(opposite);
+        let (x0, x1) = (ax.min(bx), ax.max(bx) + 1);
+        let (y0, y1) = (ay.min(by), ay.max(by) + 1);
+        let p = &self.outside_prefix;
+        p[y1][x1] + p[y0][x0] - p[y0][x1] - p[y1][x0] == 0
+    }
 }
 #[derive(PartialEq, Eq, Debug, Clone)]
 struct Tile {
@@ -81,4 +193,36 @@ mod tests {
         let actual = InstructionSet::from(PUZZLE_INPUT);
         assert_eq!(actual.get_largest_area(), 50);
     }
+
+    #[test]
+    fn test_get_largest_enclosed_area() {
+        let actual = InstructionSet::from(PUZZLE_INPUT);
+        assert_eq!(actual.get_largest_enclosed_area(), 24);
+    }
+
+    #[test_case(Tile::new(9, 5), Tile::new(2, 3), true)]
+    #[test_case(Tile::new(7, 3), Tile::new(11, 1), true)]
+    #[test_case(Tile::new(2, 5), Tile::new(11, 1), false)]
+    #[test_case(Tile::new(7, 1), Tile::new(2, 5), false)]
+    #[test_case(Tile::new(9, 7), Tile::new(9, 5), true; "along an edge")]
+    fn test_is_enclosed(t1: Tile, t2: Tile, expected: bool) {
+        let floor = CompressedFloor::new(&InstructionSet::from(PUZZLE_INPUT).0);
+        assert_eq!(floor.is_enclosed(&t1, &t2), expected);
+    }
+
+    #[test]
+    fn test_enclosed_area_around_a_notch() {
+        // A U shape: the widest rectangles span the notch and must be rejected.
+        let actual = InstructionSet::from("0,0\n10,0\n10,10\n6,10\n6,2\n4,2\n4,10\n0,10\n");
+        assert_eq!(actual.get_largest_area(), 121);
+        assert_eq!(actual.get_largest_enclosed_area(), 5 * 11);
+    }
+
+    #[test]
+    fn test_enclosed_area_around_a_zero_width_notch() {
+        // The notch walls sit on neighbouring columns, so no tile lies between
+        // them and the whole square is red or green.
+        let actual = InstructionSet::from("0,0\n10,0\n10,10\n5,10\n5,2\n4,2\n4,10\n0,10\n");
+        assert_eq!(actual.get_largest_enclosed_area(), 121);
+    }
 }